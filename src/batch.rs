@@ -0,0 +1,245 @@
+use crate::hash;
+
+/// Computes [`hash`] for every `(r, pubkey, digest)` in `inputs`.
+///
+/// On x86_64 the challenges are computed four at a time in AVX2 lanes when the
+/// CPU supports it, then two at a time in SSE2 lanes, with the scalar [`hash`]
+/// picking up whatever is left.
+pub fn hash_batch(inputs: &[(&[u8; 32], &[u8; 32], &[u8; 32])]) -> Vec<[u8; 64]> {
+    let mut result = Vec::with_capacity(inputs.len());
+    #[allow(unused_mut)]
+    let mut rest = inputs;
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") {
+            let mut chunks = rest.chunks_exact(4);
+            for chunk in &mut chunks {
+                // Safety: AVX2 support was detected above
+                result.extend(unsafe { x86::avx2::hash(chunk.try_into().unwrap()) });
+            }
+            rest = chunks.remainder();
+        }
+        let mut chunks = rest.chunks_exact(2);
+        for chunk in &mut chunks {
+            // Safety: SSE2 is part of the x86_64 baseline
+            result.extend(unsafe { x86::sse2::hash(chunk.try_into().unwrap()) });
+        }
+        rest = chunks.remainder();
+    }
+    result.extend(
+        rest.iter()
+            .map(|(r, pubkey, digest)| hash(r, pubkey, digest)),
+    );
+    result
+}
+
+// Generates a challenge hash over `$n` independent inputs, one per 64-bit lane
+// of `$vec`, from the same round generator as the scalar compression
+#[cfg(target_arch = "x86_64")]
+macro_rules! lanes {
+    (
+        $name:ident, $feature:literal, $vec:ty, $n:literal,
+        $add:ident, $xor:ident, $and:ident, $andnot:ident, $or:ident, $srli:ident, $slli:ident
+    ) => {
+        pub(super) mod $name {
+            use core::arch::x86_64::*;
+            use core::mem::transmute;
+
+            #[derive(Clone, Copy)]
+            #[repr(transparent)]
+            struct Lanes($vec);
+
+            impl Lanes {
+                #[inline]
+                #[target_feature(enable = $feature)]
+                unsafe fn wrapping_add(self, rhs: Self) -> Self {
+                    Self($add(self.0, rhs.0))
+                }
+            }
+
+            const fn from_array(x: [u64; $n]) -> Lanes {
+                unsafe { transmute::<[u64; $n], Lanes>(x) }
+            }
+
+            const fn to_array(x: Lanes) -> [u64; $n] {
+                unsafe { transmute::<Lanes, [u64; $n]>(x) }
+            }
+
+            const fn splat(x: u64) -> Lanes {
+                from_array([x; $n])
+            }
+
+            const IV: [Lanes; 8] = {
+                let mut iv = [splat(0); 8];
+                let mut i = 0;
+                while i < 8 {
+                    iv[i] = splat(crate::IV[i]);
+                    i += 1;
+                }
+                iv
+            };
+
+            const K: [Lanes; 80] = {
+                let mut k = [splat(0); 80];
+                let mut i = 0;
+                while i < 80 {
+                    k[i] = splat(crate::K[i]);
+                    i += 1;
+                }
+                k
+            };
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn xor(x: Lanes, y: Lanes) -> Lanes {
+                Lanes($xor(x.0, y.0))
+            }
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn rotr<const N: i32, const M: i32>(x: Lanes) -> Lanes {
+                Lanes($or($srli::<N>(x.0), $slli::<M>(x.0)))
+            }
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn gamma0(x: Lanes) -> Lanes {
+                xor(
+                    xor(rotr::<1, 63>(x), rotr::<8, 56>(x)),
+                    Lanes($srli::<7>(x.0)),
+                )
+            }
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn gamma1(x: Lanes) -> Lanes {
+                xor(
+                    xor(rotr::<19, 45>(x), rotr::<61, 3>(x)),
+                    Lanes($srli::<6>(x.0)),
+                )
+            }
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn ch(x: Lanes, y: Lanes, z: Lanes) -> Lanes {
+                xor(Lanes($and(x.0, y.0)), Lanes($andnot(x.0, z.0)))
+            }
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn maj(x: Lanes, y: Lanes, z: Lanes) -> Lanes {
+                xor(
+                    xor(Lanes($and(x.0, y.0)), Lanes($and(x.0, z.0))),
+                    Lanes($and(y.0, z.0)),
+                )
+            }
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn sigma0(x: Lanes) -> Lanes {
+                xor(xor(rotr::<28, 36>(x), rotr::<34, 30>(x)), rotr::<39, 25>(x))
+            }
+
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn sigma1(x: Lanes) -> Lanes {
+                xor(xor(rotr::<14, 50>(x), rotr::<18, 46>(x)), rotr::<41, 23>(x))
+            }
+
+            #[target_feature(enable = $feature)]
+            pub(crate) unsafe fn hash(
+                inputs: &[(&[u8; 32], &[u8; 32], &[u8; 32]); $n],
+            ) -> [[u8; 64]; $n] {
+                let mut w = [splat(0); 16];
+                for (i, word) in w.iter_mut().take(12).enumerate() {
+                    let mut lanes = [0u64; $n];
+                    for (lane, (r, pubkey, digest)) in lanes.iter_mut().zip(inputs) {
+                        let bytes = [r, pubkey, digest][i / 4];
+                        let offset = (i % 4) * 8;
+                        *lane = u64::from_be_bytes(bytes[offset..offset + 8].try_into().unwrap());
+                    }
+                    *word = from_array(lanes);
+                }
+                w[12] = splat(0x8000000000000000); // Padding bit
+                w[15] = splat(0x0000000000000300); // Message length in bits
+
+                let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = IV;
+                rounds!(w, [a, b, c, d, e, f, g, h], 0, 80, 80);
+
+                let state: [[u64; $n]; 8] = [
+                    to_array(IV[0].wrapping_add(a)),
+                    to_array(IV[1].wrapping_add(b)),
+                    to_array(IV[2].wrapping_add(c)),
+                    to_array(IV[3].wrapping_add(d)),
+                    to_array(IV[4].wrapping_add(e)),
+                    to_array(IV[5].wrapping_add(f)),
+                    to_array(IV[6].wrapping_add(g)),
+                    to_array(IV[7].wrapping_add(h)),
+                ];
+                let mut result = [[0u8; 64]; $n];
+                for (lane, out) in result.iter_mut().enumerate() {
+                    for (i, word) in state.iter().enumerate() {
+                        out[i * 8..(i + 1) * 8].copy_from_slice(&word[lane].to_be_bytes());
+                    }
+                }
+                result
+            }
+        }
+    };
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    lanes!(
+        avx2,
+        "avx2",
+        __m256i,
+        4,
+        _mm256_add_epi64,
+        _mm256_xor_si256,
+        _mm256_and_si256,
+        _mm256_andnot_si256,
+        _mm256_or_si256,
+        _mm256_srli_epi64,
+        _mm256_slli_epi64
+    );
+
+    lanes!(
+        sse2,
+        "sse2",
+        __m128i,
+        2,
+        _mm_add_epi64,
+        _mm_xor_si128,
+        _mm_and_si128,
+        _mm_andnot_si128,
+        _mm_or_si128,
+        _mm_srli_epi64,
+        _mm_slli_epi64
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+
+    #[test]
+    fn matches_hash_for_every_length() {
+        let mut rng = Rng::new(18);
+        let inputs: Vec<([u8; 32], [u8; 32], [u8; 32])> = (0..11)
+            .map(|_| (rng.bytes(), rng.bytes(), rng.bytes()))
+            .collect();
+        for len in 0..=inputs.len() {
+            let batch: Vec<_> = inputs[..len]
+                .iter()
+                .map(|(r, pubkey, digest)| (r, pubkey, digest))
+                .collect();
+            let expected: Vec<_> = batch
+                .iter()
+                .map(|(r, pubkey, digest)| hash(r, pubkey, digest))
+                .collect();
+            assert_eq!(hash_batch(&batch), expected, "length {len}");
+        }
+    }
+}
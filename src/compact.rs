@@ -0,0 +1,50 @@
+use crate::{ch, gamma0, gamma1, maj, sigma0, sigma1, PreparedDigest, PreparedPublicKey, IV, K};
+
+#[inline(always)]
+pub(crate) fn compress(state: &mut [u64; 8], block: &[u64; 16]) {
+    *state = rounds(*state, block);
+}
+
+#[inline(never)]
+const fn rounds(state: [u64; 8], block: &[u64; 16]) -> [u64; 8] {
+    let mut w = *block;
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+    rolled_rounds!(w, [a, b, c, d, e, f, g, h], 0, 80);
+    [
+        state[0].wrapping_add(a),
+        state[1].wrapping_add(b),
+        state[2].wrapping_add(c),
+        state[3].wrapping_add(d),
+        state[4].wrapping_add(e),
+        state[5].wrapping_add(f),
+        state[6].wrapping_add(g),
+        state[7].wrapping_add(h),
+    ]
+}
+
+#[inline(always)]
+pub(crate) const fn challenge_words(
+    r: [u64; 4],
+    key: &PreparedPublicKey,
+    digest: &PreparedDigest,
+) -> [u64; 8] {
+    let block = [
+        r[0],
+        r[1],
+        r[2],
+        r[3],
+        key.words[0],
+        key.words[1],
+        key.words[2],
+        key.words[3],
+        digest.words[0],
+        digest.words[1],
+        digest.words[2],
+        digest.words[3],
+        0x8000000000000000, // Padding bit
+        0x0000000000000000,
+        0x0000000000000000,
+        0x0000000000000300, // Message length in bits
+    ];
+    rounds(IV, &block)
+}
@@ -0,0 +1,510 @@
+// Points on the twisted Edwards curve -x^2 + y^2 = 1 + d x^2 y^2.
+//
+// Points are kept in extended coordinates (X : Y : Z : T) with x = X/Z, y = Y/Z
+// and xy = T/Z, using the formulas of Hisil, Wong, Carter and Dawson.
+
+use core::ops::{Add, Mul, Neg, Sub};
+
+use crate::field::FieldElement;
+use crate::scalar::{Scalar, L};
+
+/// A point on edwards25519, in extended coordinates.
+///
+/// Equality compares the points themselves, not their coordinates, so two
+/// representations of the same point are equal.
+#[derive(Clone, Copy, Debug)]
+pub struct EdwardsPoint {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+    t: FieldElement,
+}
+
+// (Y + X, Y - X, 2Z, 2dT), the form a point is added in
+#[derive(Clone, Copy)]
+struct Cached {
+    y_plus_x: FieldElement,
+    y_minus_x: FieldElement,
+    z2: FieldElement,
+    t2d: FieldElement,
+}
+
+impl Cached {
+    // Picks b when choice is set and a otherwise, without branching on choice
+    fn conditional_select(a: &Self, b: &Self, choice: bool) -> Self {
+        Self {
+            y_plus_x: FieldElement::conditional_select(&a.y_plus_x, &b.y_plus_x, choice),
+            y_minus_x: FieldElement::conditional_select(&a.y_minus_x, &b.y_minus_x, choice),
+            z2: FieldElement::conditional_select(&a.z2, &b.z2, choice),
+            t2d: FieldElement::conditional_select(&a.t2d, &b.t2d, choice),
+        }
+    }
+}
+
+impl EdwardsPoint {
+    /// The neutral element, (0, 1).
+    pub const IDENTITY: Self = Self {
+        x: FieldElement::ZERO,
+        y: FieldElement::ONE,
+        z: FieldElement::ONE,
+        t: FieldElement::ZERO,
+    };
+
+    /// The base point B, with y = 4/5 and x non-negative.
+    pub const BASEPOINT: Self = Self {
+        x: FieldElement::from_limbs([
+            52811034, 25909283, 16144682, 17082669, 27570973, 30858332, 40966398, 8378388,
+            20764389, 8758491,
+        ]),
+        y: FieldElement::from_limbs([
+            40265304, 26843545, 13421772, 20132659, 26843545, 6710886, 53687091, 13421772,
+            40265318, 26843545,
+        ]),
+        z: FieldElement::ONE,
+        t: FieldElement::from_limbs([
+            28827043, 27438313, 39759291, 244362, 8635006, 11264893, 19351346, 13413597, 16611511,
+            27139452,
+        ]),
+    };
+
+    /// Decodes a point, rejecting encodings of y >= p, y values with no
+    /// matching x, and x = 0 with the sign bit set.
+    pub fn decompress(bytes: &[u8; 32]) -> Option<Self> {
+        let mut y = *bytes;
+        y[31] &= 0x7f;
+        let y = FieldElement::from_canonical_bytes(&y)?;
+        let sign = bytes[31] >> 7 == 1;
+        let point = Self::from_y(y, sign)?;
+        if sign && point.x.is_zero() {
+            return None;
+        }
+        Some(point)
+    }
+
+    /// Decodes a point the way ZIP 215 and ed25519-dalek do: y >= p is
+    /// reduced modulo p and x = 0 is accepted with either sign bit. Only y
+    /// values with no matching x are rejected.
+    pub fn decompress_lenient(bytes: &[u8; 32]) -> Option<Self> {
+        Self::from_y(FieldElement::from_bytes(bytes), bytes[31] >> 7 == 1)
+    }
+
+    fn from_y(y: FieldElement, sign: bool) -> Option<Self> {
+        // x^2 = (y^2 - 1) / (d y^2 + 1)
+        let yy = y.square();
+        let u = yy - FieldElement::ONE;
+        let v = yy * FieldElement::D + FieldElement::ONE;
+        let (is_square, x) = FieldElement::sqrt_ratio(&u, &v);
+        if !is_square {
+            return None;
+        }
+        let x = if sign { -x } else { x };
+        Some(Self {
+            x,
+            y,
+            z: FieldElement::ONE,
+            t: x * y,
+        })
+    }
+
+    /// Encodes y with the sign of x in the top bit.
+    pub fn compress(&self) -> [u8; 32] {
+        let z_inv = self.z.invert();
+        let x = self.x * z_inv;
+        let mut bytes = (self.y * z_inv).to_bytes();
+        bytes[31] |= (x.is_negative() as u8) << 7;
+        bytes
+    }
+
+    fn to_cached(self) -> Cached {
+        Cached {
+            y_plus_x: self.y + self.x,
+            y_minus_x: self.y - self.x,
+            z2: self.z + self.z,
+            t2d: self.t * FieldElement::D2,
+        }
+    }
+
+    // Completes an addition from the products of the operands, where (a, b, c,
+    // d) = ((Y1 - X1)(Y2 - X2), (Y1 + X1)(Y2 + X2), 2d T1 T2, 2 Z1 Z2)
+    #[inline(always)]
+    fn from_products(a: FieldElement, b: FieldElement, c: FieldElement, d: FieldElement) -> Self {
+        let (e, f, g, h) = (b - a, d - c, d + c, b + a);
+        Self {
+            x: e * f,
+            y: g * h,
+            z: f * g,
+            t: e * h,
+        }
+    }
+
+    fn add_cached(&self, other: &Cached) -> Self {
+        Self::from_products(
+            (self.y - self.x) * other.y_minus_x,
+            (self.y + self.x) * other.y_plus_x,
+            self.t * other.t2d,
+            self.z * other.z2,
+        )
+    }
+
+    // Adding -P swaps Y + X with Y - X and negates T
+    fn sub_cached(&self, other: &Cached) -> Self {
+        Self::from_products(
+            (self.y - self.x) * other.y_plus_x,
+            (self.y + self.x) * other.y_minus_x,
+            -(self.t * other.t2d),
+            self.z * other.z2,
+        )
+    }
+
+    /// Computes `[2]P`.
+    pub fn double(&self) -> Self {
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = self.z.square();
+        let c = c + c;
+        let h = a + b;
+        let e = h - (self.x + self.y).square();
+        let g = a - b;
+        let f = c + g;
+        Self {
+            x: e * f,
+            y: g * h,
+            z: f * g,
+            t: e * h,
+        }
+    }
+
+    /// Whether P is the neutral element.
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    /// Computes `[8]P`, clearing any small-order component.
+    pub fn mul_by_cofactor(&self) -> Self {
+        self.double().double().double()
+    }
+
+    /// Whether P lies in the eight-element torsion subgroup, i.e. `[8]P` is the
+    /// identity.
+    pub fn is_small_order(&self) -> bool {
+        self.mul_by_cofactor().is_identity()
+    }
+
+    /// Whether P lies in the prime-order subgroup, i.e. `[L]P` is the identity.
+    pub fn is_torsion_free(&self) -> bool {
+        self.mul_bytes(&L).is_identity()
+    }
+
+    // Computes [s]P with fixed 4-bit windows from the top, each multiple looked
+    // up by scanning the whole table so the access pattern does not depend on
+    // the scalar
+    fn mul_bytes(&self, scalar: &[u8; 32]) -> Self {
+        let mut table = [Self::IDENTITY.to_cached(); 16];
+        let mut point = Self::IDENTITY;
+        for entry in table.iter_mut().skip(1) {
+            point = point + self;
+            *entry = point.to_cached();
+        }
+
+        let mut result = Self::IDENTITY;
+        for i in (0..64).rev() {
+            result = result.double().double().double().double();
+            let nibble = scalar[i / 2] >> (4 * (i % 2)) & 0xf;
+            let mut multiple = table[0];
+            for (j, entry) in table.iter().enumerate().skip(1) {
+                multiple = Cached::conditional_select(&multiple, entry, j == nibble as usize);
+            }
+            result = result.add_cached(&multiple);
+        }
+        result
+    }
+
+    // The odd multiples P, 3P, ..., 15P
+    fn odd_multiples(&self) -> [Cached; 8] {
+        let double = self.double().to_cached();
+        let mut table = [self.to_cached(); 8];
+        let mut point = *self;
+        for entry in table.iter_mut().skip(1) {
+            point = point.add_cached(&double);
+            *entry = point.to_cached();
+        }
+        table
+    }
+
+    /// Computes `[a]A + [b]B` in variable time, for public scalars such as those
+    /// of signature verification.
+    pub fn vartime_double_scalar_mul_basepoint(a: &Scalar, point: &Self, b: &Scalar) -> Self {
+        let (a_naf, b_naf) = (naf(a.as_bytes()), naf(b.as_bytes()));
+        let a_table = point.odd_multiples();
+        let b_table = Self::BASEPOINT.odd_multiples();
+
+        let top = (0..256)
+            .rev()
+            .find(|&i| a_naf[i] != 0 || b_naf[i] != 0)
+            .unwrap_or(0);
+        let mut result = Self::IDENTITY;
+        for i in (0..=top).rev() {
+            result = result.double();
+            for (digit, table) in [(a_naf[i], &a_table), (b_naf[i], &b_table)] {
+                if digit > 0 {
+                    result = result.add_cached(&table[digit as usize / 2]);
+                } else if digit < 0 {
+                    result = result.sub_cached(&table[-digit as usize / 2]);
+                }
+            }
+        }
+        result
+    }
+}
+
+impl Add<&EdwardsPoint> for &EdwardsPoint {
+    type Output = EdwardsPoint;
+
+    fn add(self, other: &EdwardsPoint) -> EdwardsPoint {
+        self.add_cached(&other.to_cached())
+    }
+}
+
+impl Sub<&EdwardsPoint> for &EdwardsPoint {
+    type Output = EdwardsPoint;
+
+    fn sub(self, other: &EdwardsPoint) -> EdwardsPoint {
+        self.sub_cached(&other.to_cached())
+    }
+}
+
+impl Neg for &EdwardsPoint {
+    type Output = EdwardsPoint;
+
+    fn neg(self) -> EdwardsPoint {
+        EdwardsPoint {
+            x: -self.x,
+            y: self.y,
+            z: self.z,
+            t: -self.t,
+        }
+    }
+}
+
+/// Computes `[s]P` in constant time.
+impl Mul<&Scalar> for &EdwardsPoint {
+    type Output = EdwardsPoint;
+
+    fn mul(self, scalar: &Scalar) -> EdwardsPoint {
+        self.mul_bytes(scalar.as_bytes())
+    }
+}
+
+forward_binop!(impl Add<EdwardsPoint> for EdwardsPoint, add -> EdwardsPoint);
+forward_binop!(impl Sub<EdwardsPoint> for EdwardsPoint, sub -> EdwardsPoint);
+forward_binop!(impl Mul<Scalar> for EdwardsPoint, mul -> EdwardsPoint);
+forward_neg!(EdwardsPoint);
+
+impl PartialEq for EdwardsPoint {
+    // Compares affine coordinates, X1/Z1 = X2/Z2 and Y1/Z1 = Y2/Z2
+    fn eq(&self, other: &Self) -> bool {
+        (self.x * other.z).ct_eq(&(other.x * self.z))
+            & (self.y * other.z).ct_eq(&(other.y * self.z))
+    }
+}
+
+impl Eq for EdwardsPoint {}
+
+// Recodes a scalar into signed odd digits in [-15, 15] with at least four
+// zeros after each nonzero digit, so a 5-bit window needs only odd multiples
+fn naf(scalar: &[u8; 32]) -> [i8; 256] {
+    let mut digits = [0i8; 256];
+    for (i, digit) in digits.iter_mut().enumerate() {
+        *digit = (scalar[i / 8] >> (i % 8) & 1) as i8;
+    }
+    for i in 0..256 {
+        if digits[i] == 0 {
+            continue;
+        }
+        for b in 1..7.min(256 - i) {
+            if digits[i + b] == 0 {
+                continue;
+            }
+            let shifted = (digits[i + b] as i32) << b;
+            if digits[i] as i32 + shifted <= 15 {
+                digits[i] += shifted as i8;
+                digits[i + b] = 0;
+            } else if digits[i] as i32 - shifted >= -15 {
+                digits[i] -= shifted as i8;
+                // Carry the borrowed bit into the next zero digit
+                for digit in digits.iter_mut().skip(i + b) {
+                    if *digit == 0 {
+                        *digit = 1;
+                        break;
+                    }
+                    *digit = 0;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{hex, Rng};
+
+    // The eight points of order dividing 8, as their canonical encodings
+    const TORSION: [&str; 8] = [
+        "0100000000000000000000000000000000000000000000000000000000000000",
+        "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "0000000000000000000000000000000000000000000000000000000000000080",
+        "26e8958fc2b227b045c3f489f2ef98f0d5dfac05d3c63339b13802886d53fc05",
+        "26e8958fc2b227b045c3f489f2ef98f0d5dfac05d3c63339b13802886d53fc85",
+        "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a",
+        "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac03fa",
+    ];
+
+    fn torsion() -> [EdwardsPoint; 8] {
+        TORSION.map(|s| EdwardsPoint::decompress(&hex(s)).unwrap())
+    }
+
+    fn random_scalar(rng: &mut Rng) -> Scalar {
+        let words = rng.words::<8>();
+        let mut bytes = [0u8; 64];
+        for (chunk, word) in bytes.chunks_exact_mut(8).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        Scalar::from_bytes_mod_order_wide(&bytes)
+    }
+
+    #[test]
+    fn basepoint_matches_rfc8032() {
+        let encoding = hex("5866666666666666666666666666666666666666666666666666666666666666");
+        assert_eq!(EdwardsPoint::BASEPOINT.compress(), encoding);
+        assert_eq!(
+            EdwardsPoint::decompress(&encoding),
+            Some(EdwardsPoint::BASEPOINT)
+        );
+    }
+
+    #[test]
+    fn compress_round_trips() {
+        let mut point = EdwardsPoint::BASEPOINT;
+        for _ in 0..8 {
+            let bytes = point.compress();
+            assert_eq!(EdwardsPoint::decompress(&bytes), Some(point));
+            assert_eq!(EdwardsPoint::decompress_lenient(&bytes), Some(point));
+            assert_eq!(EdwardsPoint::decompress(&bytes).unwrap().compress(), bytes);
+            point = point.double() + EdwardsPoint::BASEPOINT;
+        }
+        for s in TORSION {
+            let bytes = hex(s);
+            assert_eq!(EdwardsPoint::decompress(&bytes).unwrap().compress(), bytes);
+        }
+        // y = 2 has no matching x under either decoder
+        let mut bytes = [0u8; 32];
+        bytes[0] = 2;
+        assert!(EdwardsPoint::decompress(&bytes).is_none());
+        assert!(EdwardsPoint::decompress_lenient(&bytes).is_none());
+    }
+
+    #[test]
+    fn decompress_rejects_non_canonical_encodings() {
+        // (y, canonical encoding of the lenient result)
+        let cases = [
+            // y = p, reducing to y = 0
+            (
+                "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+            ),
+            // y = p + 1, reducing to the identity
+            (
+                "eeffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+                "0100000000000000000000000000000000000000000000000000000000000000",
+            ),
+            // The identity, x = 0, with the sign bit set
+            (
+                "0100000000000000000000000000000000000000000000000000000000000080",
+                "0100000000000000000000000000000000000000000000000000000000000000",
+            ),
+            // y = -1, x = 0, with the sign bit set
+            (
+                "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            ),
+        ];
+        for (bytes, lenient) in cases {
+            let bytes = hex(bytes);
+            assert!(EdwardsPoint::decompress(&bytes).is_none());
+            assert_eq!(
+                EdwardsPoint::decompress_lenient(&bytes).unwrap().compress(),
+                hex(lenient)
+            );
+        }
+    }
+
+    #[test]
+    fn group_law_holds() {
+        let b = EdwardsPoint::BASEPOINT;
+        let p = b.double() + b;
+        assert_eq!(p + p, p.double());
+        assert!((p - p).is_identity());
+        assert!((p + -p).is_identity());
+        assert_eq!(p + EdwardsPoint::IDENTITY, p);
+        assert_eq!(p - b, b.double());
+        assert_ne!(p, b);
+        for t in torsion() {
+            assert_eq!(t + t, t.double());
+            assert!((t - t).is_identity());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn scalar_mul_matches_vartime() {
+        let mut rng = Rng::new(30);
+        let b = EdwardsPoint::BASEPOINT;
+        let zero = Scalar::ZERO;
+        let p = b * random_scalar(&mut rng);
+        let mut scalars = vec![Scalar::ZERO, Scalar::ONE, -Scalar::ONE];
+        scalars.extend((0..4).map(|_| random_scalar(&mut rng)));
+        for k in &scalars {
+            let expected = b * k;
+            assert_eq!(
+                EdwardsPoint::vartime_double_scalar_mul_basepoint(&zero, &p, k),
+                expected
+            );
+            assert_eq!(
+                EdwardsPoint::vartime_double_scalar_mul_basepoint(k, &b, &zero),
+                expected
+            );
+            assert_eq!(
+                EdwardsPoint::vartime_double_scalar_mul_basepoint(k, &p, k),
+                p * k + expected
+            );
+        }
+        assert!((b * -Scalar::ONE + b).is_identity());
+        assert_eq!(b * (Scalar::ONE + Scalar::ONE), b.double());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn torsion_checks_classify_points() {
+        let torsion = torsion();
+        for (i, t) in torsion.iter().enumerate() {
+            assert!(t.is_small_order());
+            // Only the identity is both small-order and torsion-free
+            assert_eq!(t.is_torsion_free(), i == 0);
+            for (j, u) in torsion.iter().enumerate() {
+                assert_eq!(t == u, i == j);
+            }
+        }
+        let b = EdwardsPoint::BASEPOINT;
+        assert!(!b.is_small_order());
+        assert!(b.is_torsion_free());
+        let mixed = b + torsion[4];
+        assert!(!mixed.is_small_order());
+        assert!(!mixed.is_torsion_free());
+        assert_eq!(mixed.mul_by_cofactor(), b.mul_by_cofactor());
+    }
+}
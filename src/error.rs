@@ -0,0 +1,119 @@
+use core::fmt;
+
+/// Errors returned by the slice-based entry points.
+///
+/// Converts into a `u64` custom program error code. Codes start at 1 because a
+/// return value of 0 means success.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// R is not 32 bytes long.
+    InvalidRLength,
+    /// The public key is not 32 bytes long.
+    InvalidPublicKeyLength,
+    /// The message digest is not 32 bytes long.
+    InvalidDigestLength,
+    /// The signature is not 64 bytes long.
+    InvalidSignatureLength,
+}
+
+impl From<Error> for u64 {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::InvalidRLength => 1,
+            Error::InvalidPublicKeyLength => 2,
+            Error::InvalidDigestLength => 3,
+            Error::InvalidSignatureLength => 4,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Error::InvalidRLength => "R must be 32 bytes",
+            Error::InvalidPublicKeyLength => "public key must be 32 bytes",
+            Error::InvalidDigestLength => "message digest must be 32 bytes",
+            Error::InvalidSignatureLength => "signature must be 64 bytes",
+        })
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Reasons [`crate::verify`] and [`crate::verify_with_policy`] reject a
+/// signature.
+///
+/// Converts into a `u64` custom program error code, continuing on from the
+/// codes of [`Error`] so the two can share one program's error space.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VerifyError {
+    /// S is not reduced modulo the group order L.
+    InvalidS,
+    /// The public key is not a point encoding the policy accepts.
+    InvalidPublicKey,
+    /// R is not a point encoding the policy accepts.
+    InvalidR,
+    /// The verification equation does not hold.
+    InvalidSignature,
+}
+
+impl From<VerifyError> for u64 {
+    fn from(error: VerifyError) -> Self {
+        match error {
+            VerifyError::InvalidS => 5,
+            VerifyError::InvalidPublicKey => 6,
+            VerifyError::InvalidR => 7,
+            VerifyError::InvalidSignature => 8,
+        }
+    }
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            VerifyError::InvalidS => "S is not reduced modulo L",
+            VerifyError::InvalidPublicKey => "public key is not an accepted point encoding",
+            VerifyError::InvalidR => "R is not an accepted point encoding",
+            VerifyError::InvalidSignature => "signature does not verify",
+        })
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Reasons a [`crate::ChallengeState`] cannot be restored or advanced.
+///
+/// Converts into a `u64` custom program error code, continuing on from the
+/// codes of [`VerifyError`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StateError {
+    /// The stored byte counter is below 64, so R and A were never absorbed, or
+    /// is too large for the message offset to fit in a `usize`.
+    InvalidCounter,
+    /// The message is shorter than the bytes already absorbed from it.
+    MessageTooShort,
+    /// Part of the message has not been absorbed yet.
+    Incomplete,
+}
+
+impl From<StateError> for u64 {
+    fn from(error: StateError) -> Self {
+        match error {
+            StateError::InvalidCounter => 9,
+            StateError::MessageTooShort => 10,
+            StateError::Incomplete => 11,
+        }
+    }
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StateError::InvalidCounter => "challenge state byte counter is out of range",
+            StateError::MessageTooShort => "message is shorter than the bytes already absorbed",
+            StateError::Incomplete => "message has not been fully absorbed",
+        })
+    }
+}
+
+impl std::error::Error for StateError {}
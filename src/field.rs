@@ -0,0 +1,581 @@
+//! Arithmetic modulo p = 2^255 - 19.
+//!
+//! Elements are held in ten signed limbs of alternately 26 and 25 bits (radix
+//! 2^25.5). Limb i starts at bit ceil(25.5 i), so the product of two odd limbs
+//! weighs one bit more than limb i + j and is doubled into it. Every operation
+//! leaves its result with the limbs carried, so results can be fed straight
+//! back into a multiply.
+//!
+//! Arithmetic, comparisons, [`FieldElement::conditional_select`] and
+//! [`FieldElement::sqrt_ratio`] run in constant time. Only the `Option` and
+//! `bool` results themselves are branched on.
+
+use core::ops::{Add, Mul, Neg, Sub};
+
+// Bit offset of each limb
+const OFFSETS: [u32; 10] = [0, 26, 51, 77, 102, 128, 153, 179, 204, 230];
+
+/// An element of GF(2^255 - 19).
+#[derive(Clone, Copy, Debug)]
+pub struct FieldElement([i64; 10]);
+
+impl FieldElement {
+    pub const ZERO: Self = Self([0; 10]);
+    pub const ONE: Self = Self([1, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    // -121665/121666
+    pub(crate) const D: Self = Self([
+        56195235, 13857412, 51736253, 6949390, 114729, 24766616, 60832955, 30306712, 48412415,
+        21499315,
+    ]);
+    // 2 * D
+    pub(crate) const D2: Self = Self([
+        45281625, 27714825, 36363642, 13898781, 229458, 15978800, 54557047, 27058993, 29715967,
+        9444199,
+    ]);
+    /// 2^((p - 1) / 4), a square root of -1.
+    pub const SQRT_M1: Self = Self([
+        34513072, 25610706, 9377949, 3500415, 12389472, 33281959, 41962654, 31548777, 326685,
+        11406482,
+    ]);
+
+    pub(crate) const fn from_limbs(limbs: [i64; 10]) -> Self {
+        Self(limbs)
+    }
+
+    /// Decodes a little-endian integer, ignoring the top bit. The result is
+    /// reduced modulo p, so encodings of values >= p are accepted.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Self {
+        let mut words = [0u64; 4];
+        for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(8)) {
+            *word = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        words[3] &= (1 << 63) - 1;
+        let mut limbs = [0i64; 10];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let (word, shift) = ((OFFSETS[i] / 64) as usize, OFFSETS[i] % 64);
+            let mut bits = words[word] >> shift;
+            if shift + width(i) > 64 {
+                bits |= words[word + 1] << (64 - shift);
+            }
+            *limb = (bits & ((1 << width(i)) - 1)) as i64;
+        }
+        Self(limbs)
+    }
+
+    /// Decodes a little-endian integer, rejecting values >= p and encodings
+    /// with the top bit set.
+    pub fn from_canonical_bytes(bytes: &[u8; 32]) -> Option<Self> {
+        let element = Self::from_bytes(bytes);
+        let mut diff = 0u8;
+        for (x, y) in element.to_bytes().iter().zip(bytes) {
+            diff |= x ^ y;
+        }
+        (diff == 0).then_some(element)
+    }
+
+    /// Encodes the canonical representative in [0, p) as little-endian bytes.
+    pub fn to_bytes(self) -> [u8; 32] {
+        let mut h = self.reduce().0;
+        // q is 1 when h >= p and 0 otherwise
+        let mut q = (19 * h[9] + (1 << 24)) >> 25;
+        for (i, limb) in h.iter().enumerate() {
+            q = (limb + q) >> width(i);
+        }
+        h[0] += 19 * q;
+        for i in 0..9 {
+            let carry = h[i] >> width(i);
+            h[i + 1] += carry;
+            h[i] -= carry << width(i);
+        }
+        h[9] &= (1 << 25) - 1;
+
+        let mut words = [0u64; 4];
+        for (i, &limb) in h.iter().enumerate() {
+            let (word, shift) = ((OFFSETS[i] / 64) as usize, OFFSETS[i] % 64);
+            words[word] |= (limb as u64) << shift;
+            if shift + width(i) > 64 {
+                words[word + 1] |= (limb as u64) >> (64 - shift);
+            }
+        }
+        let mut bytes = [0u8; 32];
+        for (chunk, word) in bytes.chunks_exact_mut(8).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+
+    // Carries every limb into the next, leaving each one within about half its
+    // width either side of zero
+    #[inline(always)]
+    fn reduce(self) -> Self {
+        let mut h = self.0;
+        for i in [0, 4, 1, 5, 2, 6, 3, 7, 4, 8, 9, 0] {
+            let carry = (h[i] + (1 << (width(i) - 1))) >> width(i);
+            h[i] -= carry << width(i);
+            if i == 9 {
+                h[0] += 19 * carry;
+            } else {
+                h[i + 1] += carry;
+            }
+        }
+        Self(h)
+    }
+
+    /// Computes x^2, with about half the limb products of a multiply.
+    pub fn square(&self) -> Self {
+        let f = &self.0;
+        let mut t = [0i64; 19];
+        for i in 0..10 {
+            for j in i..10 {
+                // Off-diagonal products appear twice
+                let m = if i & j & 1 == 1 { 2 } else { 1 };
+                let n = if i == j { 1 } else { 2 };
+                t[i + j] += f[i] * f[j] * m * n;
+            }
+        }
+        fold(t)
+    }
+
+    // Squares k times
+    fn pow2k(&self, k: u32) -> Self {
+        let mut x = *self;
+        for _ in 0..k {
+            x = x.square();
+        }
+        x
+    }
+
+    // Returns (x^(2^250 - 1), x^11), shared by the inversion and square root
+    // addition chains
+    fn pow22501(&self) -> (Self, Self) {
+        let t0 = self.square();
+        let t1 = t0.pow2k(2);
+        let t2 = self * t1;
+        let t3 = t0 * t2;
+        let t4 = t3.square();
+        let t5 = t2 * t4;
+        let t7 = t5.pow2k(5) * t5;
+        let t9 = t7.pow2k(10) * t7;
+        let t11 = t9.pow2k(20) * t9;
+        let t13 = t11.pow2k(10) * t7;
+        let t15 = t13.pow2k(50) * t13;
+        let t17 = t15.pow2k(100) * t15;
+        let t19 = t17.pow2k(50) * t13;
+        (t19, t3)
+    }
+
+    /// Computes 1/x as x^(p - 2). Zero maps to zero.
+    pub fn invert(&self) -> Self {
+        let (t19, t3) = self.pow22501();
+        t19.pow2k(5) * t3
+    }
+
+    // x^((p - 5) / 8)
+    fn pow_p58(&self) -> Self {
+        let (t19, _) = self.pow22501();
+        t19.pow2k(2) * self
+    }
+
+    /// Computes the non-negative square root of u/v, returning whether u/v is
+    /// a square. When it is not, the root of i * u/v is returned instead, and
+    /// when v is zero the result is `(u == 0, 0)`.
+    pub fn sqrt_ratio(u: &Self, v: &Self) -> (bool, Self) {
+        let v3 = v.square() * v;
+        let v7 = v3.square() * v;
+        let r = u * v3 * (u * v7).pow_p58();
+        let check = v * r.square();
+
+        let correct = check.ct_eq(u);
+        let flipped = check.ct_eq(&-u);
+        let flipped_i = check.ct_eq(&(-u * Self::SQRT_M1));
+        let r = Self::conditional_select(&r, &(r * Self::SQRT_M1), flipped | flipped_i);
+        let r = Self::conditional_select(&r, &-r, r.is_negative());
+        (correct | flipped, r)
+    }
+
+    /// Whether the canonical encoding is odd, the sign of x in point encodings.
+    pub fn is_negative(&self) -> bool {
+        self.to_bytes()[0] & 1 == 1
+    }
+
+    /// Whether x = 0.
+    pub fn is_zero(&self) -> bool {
+        self.ct_eq(&Self::ZERO)
+    }
+
+    /// Compares canonical encodings without branching on their contents.
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        let (a, b) = (self.to_bytes(), other.to_bytes());
+        let mut diff = 0u8;
+        for (x, y) in a.iter().zip(b) {
+            diff |= x ^ y;
+        }
+        diff == 0
+    }
+
+    /// Picks `b` when `choice` is set and `a` otherwise, without branching on
+    /// `choice`.
+    pub fn conditional_select(a: &Self, b: &Self, choice: bool) -> Self {
+        let mask = -(choice as i64);
+        let mut h = a.0;
+        for (limb, other) in h.iter_mut().zip(b.0) {
+            *limb ^= (*limb ^ other) & mask;
+        }
+        Self(h)
+    }
+}
+
+#[inline(always)]
+const fn width(i: usize) -> u32 {
+    26 - (i & 1) as u32
+}
+
+// Folds limbs 10 and up back in, since 2^255 = 19 (mod p), then carries
+#[inline(always)]
+fn fold(mut t: [i64; 19]) -> FieldElement {
+    for i in 0..9 {
+        t[i] += 19 * t[i + 10];
+    }
+    FieldElement(t[..10].try_into().unwrap()).reduce()
+}
+
+impl Add<&FieldElement> for &FieldElement {
+    type Output = FieldElement;
+
+    #[inline(always)]
+    fn add(self, other: &FieldElement) -> FieldElement {
+        let mut h = self.0;
+        for (limb, other) in h.iter_mut().zip(other.0) {
+            *limb += other;
+        }
+        FieldElement(h).reduce()
+    }
+}
+
+impl Sub<&FieldElement> for &FieldElement {
+    type Output = FieldElement;
+
+    #[inline(always)]
+    fn sub(self, other: &FieldElement) -> FieldElement {
+        let mut h = self.0;
+        for (limb, other) in h.iter_mut().zip(other.0) {
+            *limb -= other;
+        }
+        FieldElement(h).reduce()
+    }
+}
+
+impl Mul<&FieldElement> for &FieldElement {
+    type Output = FieldElement;
+
+    fn mul(self, other: &FieldElement) -> FieldElement {
+        let (f, g) = (&self.0, &other.0);
+        let mut t = [0i64; 19];
+        for i in 0..10 {
+            for j in 0..10 {
+                // Two odd limbs sit half a bit short of their product's weight
+                let m = if i & j & 1 == 1 { 2 } else { 1 };
+                t[i + j] += f[i] * g[j] * m;
+            }
+        }
+        fold(t)
+    }
+}
+
+impl Neg for &FieldElement {
+    type Output = FieldElement;
+
+    #[inline(always)]
+    fn neg(self) -> FieldElement {
+        FieldElement::ZERO - self
+    }
+}
+
+forward_binop!(impl Add<FieldElement> for FieldElement, add -> FieldElement);
+forward_binop!(impl Sub<FieldElement> for FieldElement, sub -> FieldElement);
+forward_binop!(impl Mul<FieldElement> for FieldElement, mul -> FieldElement);
+forward_neg!(FieldElement);
+
+impl PartialEq for FieldElement {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other)
+    }
+}
+
+impl Eq for FieldElement {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+
+    // Reference arithmetic on little-endian u64 words by shift-and-subtract
+    // reduction modulo p, sharing nothing with the limb code above
+    type Int = [u64; 4];
+
+    const P: Int = [
+        0xffffffffffffffed,
+        0xffffffffffffffff,
+        0xffffffffffffffff,
+        0x7fffffffffffffff,
+    ];
+
+    fn geq(a: &Int, b: &Int) -> bool {
+        for i in (0..4).rev() {
+            if a[i] != b[i] {
+                return a[i] > b[i];
+            }
+        }
+        true
+    }
+
+    fn sub_in_place(a: &mut Int, b: &Int) {
+        let mut borrow = false;
+        for i in 0..4 {
+            let (x, b1) = a[i].overflowing_sub(b[i]);
+            let (x, b2) = x.overflowing_sub(borrow as u64);
+            a[i] = x;
+            borrow = b1 | b2;
+        }
+    }
+
+    // Reduces an integer of any number of words modulo p, one bit at a time
+    fn modp(x: &[u64]) -> Int {
+        let mut r = [0u64; 4];
+        for i in (0..x.len() * 64).rev() {
+            // r < p < 2^255, so shifting left cannot overflow
+            for j in (1..4).rev() {
+                r[j] = r[j] << 1 | r[j - 1] >> 63;
+            }
+            r[0] = r[0] << 1 | (x[i / 64] >> (i % 64) & 1);
+            if geq(&r, &P) {
+                sub_in_place(&mut r, &P);
+            }
+        }
+        r
+    }
+
+    fn ref_add(a: &Int, b: &Int) -> Int {
+        let mut sum = [0u64; 5];
+        let mut carry = 0u128;
+        for i in 0..4 {
+            carry += a[i] as u128 + b[i] as u128;
+            sum[i] = carry as u64;
+            carry >>= 64;
+        }
+        sum[4] = carry as u64;
+        modp(&sum)
+    }
+
+    fn ref_neg(a: &Int) -> Int {
+        let mut r = P;
+        sub_in_place(&mut r, &modp(a));
+        modp(&r)
+    }
+
+    fn ref_sub(a: &Int, b: &Int) -> Int {
+        ref_add(a, &ref_neg(b))
+    }
+
+    fn ref_mul(a: &Int, b: &Int) -> Int {
+        let mut product = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                carry += a[i] as u128 * b[j] as u128 + product[i + j] as u128;
+                product[i + j] = carry as u64;
+                carry >>= 64;
+            }
+            product[i + 4] = carry as u64;
+        }
+        modp(&product)
+    }
+
+    fn ref_pow(a: &Int, e: &Int) -> Int {
+        let mut r = [1, 0, 0, 0];
+        for i in (0..256).rev() {
+            r = ref_mul(&r, &r);
+            if e[i / 64] >> (i % 64) & 1 == 1 {
+                r = ref_mul(&r, a);
+            }
+        }
+        r
+    }
+
+    // 2^((p - 1) / 4)
+    const SQRT_M1: Int = [
+        0xc4ee1b274a0ea0b0,
+        0x2f431806ad2fe478,
+        0x2b4d00993dfbd7a7,
+        0x2b8324804fc1df0b,
+    ];
+
+    fn from_bytes(bytes: &[u8; 32]) -> Int {
+        let mut x = [0u64; 4];
+        for (word, chunk) in x.iter_mut().zip(bytes.chunks_exact(8)) {
+            *word = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        x
+    }
+
+    fn to_bytes(a: &Int) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (chunk, word) in bytes.chunks_exact_mut(8).zip(a) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+
+    fn element(a: &Int) -> FieldElement {
+        FieldElement::from_bytes(&to_bytes(a))
+    }
+
+    fn check(x: &FieldElement, expected: &Int) {
+        assert_eq!(x.to_bytes(), to_bytes(&modp(expected)));
+    }
+
+    // 0, 1, 2, p - 2, p - 1, p, p + 1, 2^255 - 1 and every limb at its top value
+    fn edge_values() -> Vec<Int> {
+        let mut values = vec![[0; 4], [1, 0, 0, 0], [2, 0, 0, 0]];
+        for delta in [-2i64, -1, 0, 1, 18] {
+            let mut x = P;
+            x[0] = x[0].wrapping_add(delta as u64);
+            values.push(x);
+        }
+        values.push([u64::MAX, u64::MAX, u64::MAX, u64::MAX >> 1]);
+        values.push([1 << 63, 0, 0, 0]);
+        values.push([0, 0, 0, 1 << 62]);
+        values
+    }
+
+    fn random(rng: &mut Rng) -> Int {
+        let mut x: Int = rng.words();
+        x[3] >>= 1;
+        x
+    }
+
+    #[test]
+    fn arithmetic_matches_reference() {
+        let mut rng = Rng::new(22);
+        let mut values = edge_values();
+        values.extend((0..8).map(|_| random(&mut rng)));
+        for a in &values {
+            let x = element(a);
+            check(&-x, &ref_neg(a));
+            check(&x.square(), &ref_mul(a, a));
+            for b in &values {
+                let y = element(b);
+                check(&(x + y), &ref_add(a, b));
+                check(&(x - y), &ref_sub(a, b));
+                check(&(x * y), &ref_mul(a, b));
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn invert_matches_reference() {
+        let mut rng = Rng::new(23);
+        let mut p_minus_2 = P;
+        p_minus_2[0] -= 2;
+        let mut values = edge_values();
+        values.extend((0..4).map(|_| random(&mut rng)));
+        for a in &values {
+            check(&element(a).invert(), &ref_pow(a, &p_minus_2));
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn long_chains_stay_in_bounds() {
+        let mut rng = Rng::new(24);
+        let (mut a, b, c) = (random(&mut rng), random(&mut rng), random(&mut rng));
+        let (mut x, y, z) = (element(&a), element(&b), element(&c));
+        for i in 0..2000 {
+            x = match i % 6 {
+                0 => x * y + z,
+                1 => -(x - y) - z,
+                2 => x.square() + x + x + x,
+                3 => (x + y + z + x + y + z) * (x - z),
+                4 => x.pow2k(16) - y,
+                _ => x - y - y - y - z,
+            };
+            a = match i % 6 {
+                0 => ref_add(&ref_mul(&a, &b), &c),
+                1 => ref_sub(&ref_neg(&ref_sub(&a, &b)), &c),
+                2 => ref_add(&ref_add(&ref_add(&ref_mul(&a, &a), &a), &a), &a),
+                3 => {
+                    let sum = ref_add(&ref_add(&a, &b), &c);
+                    ref_mul(&ref_add(&sum, &sum), &ref_sub(&a, &c))
+                }
+                4 => ref_sub(&(0..16).fold(a, |a, _| ref_mul(&a, &a)), &b),
+                _ => ref_sub(&ref_sub(&ref_sub(&ref_sub(&a, &b), &b), &b), &c),
+            };
+            if i % 100 == 99 {
+                check(&x, &a);
+            }
+        }
+        check(&x, &a);
+    }
+
+    #[test]
+    fn encoding_is_canonical() {
+        let mut rng = Rng::new(25);
+        let mut values = edge_values();
+        values.extend((0..8).map(|_| random(&mut rng)));
+        for a in &values {
+            let bytes = to_bytes(a);
+            let canonical = geq(&P, a) && *a != P;
+            assert_eq!(
+                FieldElement::from_bytes(&bytes).to_bytes(),
+                to_bytes(&modp(a))
+            );
+            assert_eq!(
+                FieldElement::from_canonical_bytes(&bytes).is_some(),
+                canonical
+            );
+            // The top bit is ignored by from_bytes and rejected by
+            // from_canonical_bytes
+            let mut high = bytes;
+            high[31] |= 0x80;
+            assert_eq!(FieldElement::from_bytes(&high), element(a));
+            assert!(FieldElement::from_canonical_bytes(&high).is_none());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn sqrt_ratio_matches_reference() {
+        let mut rng = Rng::new(26);
+        let two = [2, 0, 0, 0];
+        for i in 0..16 {
+            let (r, v) = (random(&mut rng), random(&mut rng));
+            // u = r^2 v is a square over v; 2 r^2 v is not, since 2 is not a
+            // square modulo p
+            let mut u = ref_mul(&ref_mul(&r, &r), &v);
+            if i % 2 == 1 {
+                u = ref_mul(&u, &two);
+            }
+            let (was_square, root) = FieldElement::sqrt_ratio(&element(&u), &element(&v));
+            assert_eq!(was_square, i % 2 == 0);
+            assert!(!root.is_negative());
+            let root = modp(&from_bytes(&root.to_bytes()));
+            let lhs = ref_mul(&v, &ref_mul(&root, &root));
+            if was_square {
+                assert_eq!(lhs, modp(&u));
+            } else {
+                assert_eq!(lhs, ref_mul(&SQRT_M1, &u));
+            }
+        }
+        assert_eq!(
+            FieldElement::sqrt_ratio(&FieldElement::ZERO, &FieldElement::ONE),
+            (true, FieldElement::ZERO)
+        );
+        assert_eq!(
+            FieldElement::sqrt_ratio(&FieldElement::ONE, &FieldElement::ZERO),
+            (false, FieldElement::ZERO)
+        );
+        assert_eq!(to_bytes(&SQRT_M1), FieldElement::SQRT_M1.to_bytes());
+        assert_eq!(ref_mul(&SQRT_M1, &SQRT_M1), ref_neg(&[1, 0, 0, 0]));
+    }
+}
@@ -1,8 +1,62 @@
-use core::mem::MaybeUninit;
+#[macro_use]
+mod ops;
+#[macro_use]
+mod rounds;
+
+mod batch;
+#[cfg(all(
+    feature = "compact",
+    not(any(feature = "u32", target_pointer_width = "32"))
+))]
+mod compact;
+mod edwards;
+mod error;
+pub mod field;
+#[cfg(feature = "std")]
+mod parallel;
+mod prepared;
+mod resumable;
+mod scalar;
+mod sha512;
+#[cfg(any(feature = "u32", target_pointer_width = "32", test))]
+mod split;
+#[cfg(test)]
+mod testing;
+mod types;
+#[cfg(not(any(feature = "compact", feature = "u32", target_pointer_width = "32")))]
+mod unrolled;
+mod verify;
+
+#[cfg(all(
+    feature = "compact",
+    not(any(feature = "u32", target_pointer_width = "32"))
+))]
+use compact::{challenge_words, compress};
+#[cfg(any(feature = "u32", target_pointer_width = "32"))]
+use split::{challenge_words, compress};
+#[cfg(not(any(feature = "compact", feature = "u32", target_pointer_width = "32")))]
+use unrolled::{challenge_words, compress};
+
+pub use batch::hash_batch;
+pub use edwards::EdwardsPoint;
+pub use error::{Error, StateError, VerifyError};
+#[cfg(feature = "std")]
+pub use parallel::hash_parallel;
+pub use prepared::{
+    hash_many, hash_many_into, hash_with_prepared, hash_with_prepared_digest, PreparedDigest,
+    PreparedPublicKey,
+};
+pub use resumable::{ChallengeState, Progress};
+pub use scalar::Scalar;
+pub use sha512::{sha512_t_iv, Sha384, Sha512, Sha512Trunc, Sha512_224, Sha512_256};
+pub use types::{Challenge, MessageDigest, PublicKey, Signature, SignatureR};
+pub use verify::{
+    verify, verify_message, verify_message_with_policy, verify_with_policy, VerifyPolicy,
+};
 
 #[inline(always)]
 const fn rotr(x: u64, n: u32) -> u64 {
-    (x >> n) | (x << (64 - n))
+    x.rotate_right(n)
 }
 
 #[inline(always)]
@@ -35,1356 +89,565 @@ const fn sigma1(x: u64) -> u64 {
     rotr(x, 14) ^ rotr(x, 18) ^ rotr(x, 41)
 }
 
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+// Round constants (FIPS 180-4 §4.2.3)
+const K: [u64; 80] = [
+    0x428A2F98D728AE22,
+    0x7137449123EF65CD,
+    0xB5C0FBCFEC4D3B2F,
+    0xE9B5DBA58189DBBC,
+    0x3956C25BF348B538,
+    0x59F111F1B605D019,
+    0x923F82A4AF194F9B,
+    0xAB1C5ED5DA6D8118,
+    0xD807AA98A3030242,
+    0x12835B0145706FBE,
+    0x243185BE4EE4B28C,
+    0x550C7DC3D5FFB4E2,
+    0x72BE5D74F27B896F,
+    0x80DEB1FE3B1696B1,
+    0x9BDC06A725C71235,
+    0xC19BF174CF692694,
+    0xE49B69C19EF14AD2,
+    0xEFBE4786384F25E3,
+    0x0FC19DC68B8CD5B5,
+    0x240CA1CC77AC9C65,
+    0x2DE92C6F592B0275,
+    0x4A7484AA6EA6E483,
+    0x5CB0A9DCBD41FBD4,
+    0x76F988DA831153B5,
+    0x983E5152EE66DFAB,
+    0xA831C66D2DB43210,
+    0xB00327C898FB213F,
+    0xBF597FC7BEEF0EE4,
+    0xC6E00BF33DA88FC2,
+    0xD5A79147930AA725,
+    0x06CA6351E003826F,
+    0x142929670A0E6E70,
+    0x27B70A8546D22FFC,
+    0x2E1B21385C26C926,
+    0x4D2C6DFC5AC42AED,
+    0x53380D139D95B3DF,
+    0x650A73548BAF63DE,
+    0x766A0ABB3C77B2A8,
+    0x81C2C92E47EDAEE6,
+    0x92722C851482353B,
+    0xA2BFE8A14CF10364,
+    0xA81A664BBC423001,
+    0xC24B8B70D0F89791,
+    0xC76C51A30654BE30,
+    0xD192E819D6EF5218,
+    0xD69906245565A910,
+    0xF40E35855771202A,
+    0x106AA07032BBD1B8,
+    0x19A4C116B8D2D0C8,
+    0x1E376C085141AB53,
+    0x2748774CDF8EEB99,
+    0x34B0BCB5E19B48A8,
+    0x391C0CB3C5C95A63,
+    0x4ED8AA4AE3418ACB,
+    0x5B9CCA4F7763E373,
+    0x682E6FF3D6B2B8A3,
+    0x748F82EE5DEFB2FC,
+    0x78A5636F43172F60,
+    0x84C87814A1F0AB72,
+    0x8CC702081A6439EC,
+    0x90BEFFFA23631E28,
+    0xA4506CEBDE82BDE9,
+    0xBEF9A3F7B2C67915,
+    0xC67178F2E372532B,
+    0xCA273ECEEA26619C,
+    0xD186B8C721C0C207,
+    0xEADA7DD6CDE0EB1E,
+    0xF57D4F7FEE6ED178,
+    0x06F067AA72176FBA,
+    0x0A637DC5A2C898A6,
+    0x113F9804BEF90DAE,
+    0x1B710B35131C471B,
+    0x28DB77F523047D84,
+    0x32CAAB7B40C72493,
+    0x3C9EBE0A15C9BEBC,
+    0x431D67C49C100D4C,
+    0x4CC5D4BECB3E42B6,
+    0x597F299CFC657E2A,
+    0x5FCB6FAB3AD6FAEC,
+    0x6C44198C4A475817,
+];
+
 #[inline(always)]
-pub fn hash(r: &[u8; 32], pubkey: &[u8; 32], digest: &[u8; 32]) -> [u8; 64] {
-    let mut words = MaybeUninit::<[u64; 80]>::uninit();
-    let w = unsafe { words.assume_init_mut() };
-    w[0] = u64::from_be_bytes([r[0], r[1], r[2], r[3], r[4], r[5], r[6], r[7]]);
-    w[1] = u64::from_be_bytes([r[8], r[9], r[10], r[11], r[12], r[13], r[14], r[15]]);
-    w[2] = u64::from_be_bytes([r[16], r[17], r[18], r[19], r[20], r[21], r[22], r[23]]);
-    w[3] = u64::from_be_bytes([r[24], r[25], r[26], r[27], r[28], r[29], r[30], r[31]]);
-    w[4] = u64::from_be_bytes([
-        pubkey[0], pubkey[1], pubkey[2], pubkey[3], pubkey[4], pubkey[5], pubkey[6], pubkey[7],
-    ]);
-    w[5] = u64::from_be_bytes([
-        pubkey[8], pubkey[9], pubkey[10], pubkey[11], pubkey[12], pubkey[13], pubkey[14],
-        pubkey[15],
-    ]);
-    w[6] = u64::from_be_bytes([
-        pubkey[16], pubkey[17], pubkey[18], pubkey[19], pubkey[20], pubkey[21], pubkey[22],
-        pubkey[23],
-    ]);
-    w[7] = u64::from_be_bytes([
-        pubkey[24], pubkey[25], pubkey[26], pubkey[27], pubkey[28], pubkey[29], pubkey[30],
-        pubkey[31],
-    ]);
-    w[8] = u64::from_be_bytes([
-        digest[0], digest[1], digest[2], digest[3], digest[4], digest[5], digest[6], digest[7],
-    ]);
-    w[9] = u64::from_be_bytes([
-        digest[8], digest[9], digest[10], digest[11], digest[12], digest[13], digest[14],
-        digest[15],
-    ]);
-    w[10] = u64::from_be_bytes([
-        digest[16], digest[17], digest[18], digest[19], digest[20], digest[21], digest[22],
-        digest[23],
-    ]);
-    w[11] = u64::from_be_bytes([
-        digest[24], digest[25], digest[26], digest[27], digest[28], digest[29], digest[30],
-        digest[31],
-    ]);
-    w[12] = 0x8000000000000000; // Padding bit
-    w[13] = 0x0000000000000000; // Explicity set this
-    w[14] = 0x0000000000000000; // And this
-    w[15] = 0x0000000000000300;
-    for i in 16..80 {
-        w[i] = w[i - 16]
-            .wrapping_add(gamma0(w[i - 15]))
-            .wrapping_add(w[i - 7])
-            .wrapping_add(gamma1(w[i - 2]));
+fn store(state: &[u64; 8], out: &mut [u8; 64]) {
+    for (i, word) in state.iter().enumerate() {
+        out[i * 8..(i + 1) * 8].copy_from_slice(&word.to_be_bytes());
     }
+}
 
-    // Round 0
-    let mut t1 = 0x5be0cd19137e2179u64
-        .wrapping_add(sigma1(0x510e527fade682d1u64))
-        .wrapping_add(ch(
-            0x510e527fade682d1u64,
-            0x9b05688c2b3e6c1fu64,
-            0x1f83d9abfb41bd6bu64,
-        ))
-        .wrapping_add(0x428A2F98D728AE22)
-        .wrapping_add(w[0]);
-    let mut t2 = 0x4334C1BEA164F555;
-    let mut h = 0x1f83d9abfb41bd6bu64;
-    let mut g = 0x9b05688c2b3e6c1fu64;
-    let mut f = 0x510e527fade682d1u64;
-    let mut e = 0xa54ff53a5f1d36f1u64.wrapping_add(t1);
-    let mut d = 0x3c6ef372fe94f82bu64;
-    let mut c = 0xbb67ae8584caa73bu64;
-    let mut b = 0x6a09e667f3bcc908u64;
-    let mut a = t1.wrapping_add(t2);
-
-    // Round 1
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x7137449123EF65CD)
-        .wrapping_add(w[1]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 2
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xB5C0FBCFEC4D3B2F)
-        .wrapping_add(w[2]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 3
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xE9B5DBA58189DBBC)
-        .wrapping_add(w[3]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 4
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x3956C25BF348B538)
-        .wrapping_add(w[4]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 5
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x59F111F1B605D019)
-        .wrapping_add(w[5]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 6
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x923F82A4AF194F9B)
-        .wrapping_add(w[6]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 7
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xAB1C5ED5DA6D8118)
-        .wrapping_add(w[7]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 8
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xD807AA98A3030242)
-        .wrapping_add(w[8]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 9
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x12835B0145706FBE)
-        .wrapping_add(w[9]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 10
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x243185BE4EE4B28C)
-        .wrapping_add(w[10]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 11
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x550C7DC3D5FFB4E2)
-        .wrapping_add(w[11]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 12
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x72BE5D74F27B896F)
-        .wrapping_add(w[12]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 13
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x80DEB1FE3B1696B1)
-        .wrapping_add(w[13]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 14
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x9BDC06A725C71235)
-        .wrapping_add(w[14]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 15
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xC19BF174CF692694)
-        .wrapping_add(w[15]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 16
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xE49B69C19EF14AD2)
-        .wrapping_add(w[16]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 17
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xEFBE4786384F25E3)
-        .wrapping_add(w[17]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 18
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x0FC19DC68B8CD5B5)
-        .wrapping_add(w[18]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 19
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x240CA1CC77AC9C65)
-        .wrapping_add(w[19]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 20
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x2DE92C6F592B0275)
-        .wrapping_add(w[20]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 21
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x4A7484AA6EA6E483)
-        .wrapping_add(w[21]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 22
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x5CB0A9DCBD41FBD4)
-        .wrapping_add(w[22]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 23
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x76F988DA831153B5)
-        .wrapping_add(w[23]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 24
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x983E5152EE66DFAB)
-        .wrapping_add(w[24]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 25
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xA831C66D2DB43210)
-        .wrapping_add(w[25]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 26
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xB00327C898FB213F)
-        .wrapping_add(w[26]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 27
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xBF597FC7BEEF0EE4)
-        .wrapping_add(w[27]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 28
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xC6E00BF33DA88FC2)
-        .wrapping_add(w[28]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 29
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xD5A79147930AA725)
-        .wrapping_add(w[29]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 30
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x06CA6351E003826F)
-        .wrapping_add(w[30]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 31
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x142929670A0E6E70)
-        .wrapping_add(w[31]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 32
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x27B70A8546D22FFC)
-        .wrapping_add(w[32]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 33
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x2E1B21385C26C926)
-        .wrapping_add(w[33]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 34
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x4D2C6DFC5AC42AED)
-        .wrapping_add(w[34]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 35
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x53380D139D95B3DF)
-        .wrapping_add(w[35]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 36
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x650A73548BAF63DE)
-        .wrapping_add(w[36]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 37
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x766A0ABB3C77B2A8)
-        .wrapping_add(w[37]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 38
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x81C2C92E47EDAEE6)
-        .wrapping_add(w[38]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 39
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x92722C851482353B)
-        .wrapping_add(w[39]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 40
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xA2BFE8A14CF10364)
-        .wrapping_add(w[40]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 41
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xA81A664BBC423001)
-        .wrapping_add(w[41]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 42
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xC24B8B70D0F89791)
-        .wrapping_add(w[42]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 43
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xC76C51A30654BE30)
-        .wrapping_add(w[43]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 44
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xD192E819D6EF5218)
-        .wrapping_add(w[44]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 45
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xD69906245565A910)
-        .wrapping_add(w[45]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 46
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xF40E35855771202A)
-        .wrapping_add(w[46]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 47
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x106AA07032BBD1B8)
-        .wrapping_add(w[47]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 48
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x19A4C116B8D2D0C8)
-        .wrapping_add(w[48]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 49
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x1E376C085141AB53)
-        .wrapping_add(w[49]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
-
-    // Round 50
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x2748774CDF8EEB99)
-        .wrapping_add(w[50]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+#[inline(always)]
+fn words32(bytes: &[u8; 32]) -> [u64; 4] {
+    let mut words = [0u64; 4];
+    for (i, word) in words.iter_mut().enumerate() {
+        *word = u64::from_be_bytes(bytes[i * 8..(i + 1) * 8].try_into().unwrap());
+    }
+    words
+}
 
-    // Round 51
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x34B0BCB5E19B48A8)
-        .wrapping_add(w[51]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+#[inline(always)]
+const fn to_bytes(state: &[u64; 8]) -> [u8; 64] {
+    let mut result = [0u8; 64];
+    // Whole words, so the copies lower to one byte-swapped store each
+    let mut i = 0;
+    while i < 8 {
+        let bytes = state[i].to_be_bytes();
+        let mut j = 0;
+        while j < 8 {
+            result[8 * i + j] = bytes[j];
+            j += 1;
+        }
+        i += 1;
+    }
+    result
+}
 
-    // Round 52
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x391C0CB3C5C95A63)
-        .wrapping_add(w[52]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+#[inline(always)]
+const fn challenge(r: &[u8; 32], pubkey: &[u8; 32], digest: &[u8; 32]) -> [u64; 8] {
+    challenge_words(
+        [
+            u64::from_be_bytes([r[0], r[1], r[2], r[3], r[4], r[5], r[6], r[7]]),
+            u64::from_be_bytes([r[8], r[9], r[10], r[11], r[12], r[13], r[14], r[15]]),
+            u64::from_be_bytes([r[16], r[17], r[18], r[19], r[20], r[21], r[22], r[23]]),
+            u64::from_be_bytes([r[24], r[25], r[26], r[27], r[28], r[29], r[30], r[31]]),
+        ],
+        &PreparedPublicKey::new(pubkey),
+        &PreparedDigest::new(digest),
+    )
+}
 
-    // Round 53
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x4ED8AA4AE3418ACB)
-        .wrapping_add(w[53]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+/// Computes SHA-512(R || A || M) for a 32-byte message.
+///
+/// This is a `const fn`, so challenges for fixed keys and messages can be
+/// computed at compile time.
+#[inline(always)]
+pub const fn hash(r: &[u8; 32], pubkey: &[u8; 32], digest: &[u8; 32]) -> [u8; 64] {
+    to_bytes(&challenge(r, pubkey, digest))
+}
 
-    // Round 54
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x5B9CCA4F7763E373)
-        .wrapping_add(w[54]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+// Known-answer check against the README vector, evaluated at compile time
+const _: () = {
+    const RESULT: [u8; 64] = [
+        0xac, 0x99, 0x4a, 0x51, 0x3c, 0x80, 0x88, 0x60, 0x27, 0x9a, 0x5a, 0x74, 0x98, 0x89, 0x73,
+        0xc8, 0x54, 0xcc, 0x96, 0x6e, 0x1c, 0x91, 0xc6, 0xa1, 0xc3, 0x27, 0x00, 0xa1, 0xfd, 0xeb,
+        0xad, 0x87, 0x1b, 0xf8, 0xc1, 0x83, 0xc1, 0x0e, 0xf5, 0xb4, 0x6f, 0xc0, 0x4c, 0x25, 0x85,
+        0xd1, 0x26, 0xd5, 0x33, 0xbd, 0xa2, 0x07, 0xa6, 0x62, 0xd8, 0x48, 0x27, 0x6d, 0x7e, 0x49,
+        0x95, 0xe6, 0x93, 0xe1,
+    ];
+    let result = hash(&[0x01; 32], &[0x02; 32], &[0x03; 32]);
+    let mut i = 0;
+    while i < 64 {
+        assert!(result[i] == RESULT[i]);
+        i += 1;
+    }
+};
 
-    // Round 55
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x682E6FF3D6B2B8A3)
-        .wrapping_add(w[55]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+/// Writes SHA-512(R || A || M) straight into `out`, e.g. a slice of account data.
+#[inline(always)]
+pub fn hash_into(r: &[u8; 32], pubkey: &[u8; 32], digest: &[u8; 32], out: &mut [u8; 64]) {
+    store(&challenge(r, pubkey, digest), out);
+}
 
-    // Round 56
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x748F82EE5DEFB2FC)
-        .wrapping_add(w[56]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+/// Writes SHA-512 of a contiguous 96-byte `R || A || M` into `out`.
+#[inline(always)]
+pub fn hash_concat_into(input: &[u8; 96], out: &mut [u8; 64]) {
+    let mut words = [0u64; 12];
+    for (i, word) in words.iter_mut().enumerate() {
+        *word = u64::from_be_bytes(input[i * 8..(i + 1) * 8].try_into().unwrap());
+    }
+    let key = PreparedPublicKey::from_words([words[4], words[5], words[6], words[7]]);
+    let digest = PreparedDigest::from_words([words[8], words[9], words[10], words[11]]);
+    let state = challenge_words([words[0], words[1], words[2], words[3]], &key, &digest);
+    store(&state, out);
+}
 
-    // Round 57
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x78A5636F43172F60)
-        .wrapping_add(w[57]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+/// Computes SHA-512 of a contiguous 96-byte `R || A || M`.
+#[inline(always)]
+pub fn hash_concat(input: &[u8; 96]) -> [u8; 64] {
+    let mut result = [0u8; 64];
+    hash_concat_into(input, &mut result);
+    result
+}
 
-    // Round 58
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x84C87814A1F0AB72)
-        .wrapping_add(w[58]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+/// Reads a 96-byte `R || A || M` from `input` and writes its 64-byte SHA-512
+/// to `out`, e.g. straight out of the instruction input into account data.
+///
+/// # Safety
+///
+/// `input` must be valid for 96 byte reads and `out` for 64 byte writes, and the
+/// two ranges must not overlap. Neither pointer needs to be aligned.
+#[inline(always)]
+pub unsafe fn hash_raw(input: *const u8, out: *mut u8) {
+    hash_concat_into(&*(input as *const [u8; 96]), &mut *(out as *mut [u8; 64]));
+}
 
-    // Round 59
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x8CC702081A6439EC)
-        .wrapping_add(w[59]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+/// Computes SHA-512(R || A || M) reduced modulo the group order L, as a
+/// canonical little-endian scalar.
+#[inline(always)]
+pub fn challenge_scalar(r: &[u8; 32], pubkey: &[u8; 32], digest: &[u8; 32]) -> [u8; 32] {
+    // The digest is read as a little-endian integer, so each big-endian state
+    // word becomes a little-endian limb once its bytes are swapped
+    scalar::reduce_wide(&challenge(r, pubkey, digest).map(u64::swap_bytes))
+}
 
-    // Round 60
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x90BEFFFA23631E28)
-        .wrapping_add(w[60]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+#[inline(always)]
+fn bytes32(bytes: &[u8], error: Error) -> Result<&[u8; 32], Error> {
+    bytes.try_into().map_err(|_| error)
+}
 
-    // Round 61
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xA4506CEBDE82BDE9)
-        .wrapping_add(w[61]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+/// Slice-checked form of [`hash`] for inputs of unknown length.
+#[inline(always)]
+pub fn try_hash(r: &[u8], pubkey: &[u8], digest: &[u8]) -> Result<[u8; 64], Error> {
+    Ok(hash(
+        bytes32(r, Error::InvalidRLength)?,
+        bytes32(pubkey, Error::InvalidPublicKeyLength)?,
+        bytes32(digest, Error::InvalidDigestLength)?,
+    ))
+}
 
-    // Round 62
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xBEF9A3F7B2C67915)
-        .wrapping_add(w[62]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+/// Slice-checked form of [`challenge_scalar`] for inputs of unknown length.
+#[inline(always)]
+pub fn try_challenge_scalar(r: &[u8], pubkey: &[u8], digest: &[u8]) -> Result<[u8; 32], Error> {
+    Ok(challenge_scalar(
+        bytes32(r, Error::InvalidRLength)?,
+        bytes32(pubkey, Error::InvalidPublicKeyLength)?,
+        bytes32(digest, Error::InvalidDigestLength)?,
+    ))
+}
 
-    // Round 63
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xC67178F2E372532B)
-        .wrapping_add(w[63]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+/// Slice-checked form of [`hash_message`] for inputs of unknown length.
+pub fn try_hash_message(r: &[u8], pubkey: &[u8], msg: &[u8]) -> Result<[u8; 64], Error> {
+    Ok(hash_message(
+        bytes32(r, Error::InvalidRLength)?,
+        bytes32(pubkey, Error::InvalidPublicKeyLength)?,
+        msg,
+    ))
+}
 
-    // Round 64
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xCA273ECEEA26619C)
-        .wrapping_add(w[64]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+/// Computes SHA-512(R || A || M) for a message of any length.
+pub fn hash_message(r: &[u8; 32], pubkey: &[u8; 32], msg: &[u8]) -> [u8; 64] {
+    hash_parts(&[r, pubkey, msg])
+}
 
-    // Round 65
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xD186B8C721C0C207)
-        .wrapping_add(w[65]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+struct Padding<const N: usize>;
+
+impl<const N: usize> Padding<N> {
+    // R || A || M, the padding bit and the 128-bit length, in 128-byte blocks
+    const BLOCKS: usize = (N + 80) / 128 + 1;
+    // Index of the word holding the last N % 8 message bytes and the padding bit
+    const TAIL: usize = 8 + N / 8;
+    // The padding bit, just after the last N % 8 message bytes
+    const PAD_BIT: u64 = 0x80 << (56 - 8 * (N % 8));
+    // Message length in bits, the last two words of the final block
+    const LEN_HI: u64 = (((N as u128 + 64) << 3) >> 64) as u64;
+    const LEN_LO: u64 = ((N as u128 + 64) << 3) as u64;
+}
 
-    // Round 66
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xEADA7DD6CDE0EB1E)
-        .wrapping_add(w[66]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+#[inline(always)]
+fn fixed_word<const N: usize>(r: &[u8; 32], pubkey: &[u8; 32], msg: &[u8; N], i: usize) -> u64 {
+    if i < 4 {
+        return u64::from_be_bytes(r[i * 8..i * 8 + 8].try_into().unwrap());
+    }
+    if i < 8 {
+        return u64::from_be_bytes(pubkey[i * 8 - 32..i * 8 - 24].try_into().unwrap());
+    }
+    if i < Padding::<N>::TAIL {
+        return u64::from_be_bytes(msg[i * 8 - 64..i * 8 - 56].try_into().unwrap());
+    }
+    if i == Padding::<N>::TAIL {
+        let mut word = [0u8; 8];
+        word[..N % 8].copy_from_slice(&msg[N - N % 8..]);
+        return u64::from_be_bytes(word) | Padding::<N>::PAD_BIT;
+    }
+    if i == Padding::<N>::BLOCKS * 16 - 2 {
+        return Padding::<N>::LEN_HI;
+    }
+    if i == Padding::<N>::BLOCKS * 16 - 1 {
+        return Padding::<N>::LEN_LO;
+    }
+    0
+}
 
-    // Round 67
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0xF57D4F7FEE6ED178)
-        .wrapping_add(w[67]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+/// Computes SHA-512(R || A || M) for a message of a fixed length `N`.
+///
+/// The block count, the position of the padding bit and the length words are
+/// constants of `N`: messages of up to 47 bytes fit in a single block and
+/// messages of 48 to 175 bytes take two.
+#[inline(always)]
+pub fn hash_fixed<const N: usize>(r: &[u8; 32], pubkey: &[u8; 32], msg: &[u8; N]) -> [u8; 64] {
+    let mut state = IV;
+    for i in 0..Padding::<N>::BLOCKS {
+        let mut block = [0u64; 16];
+        for (j, word) in block.iter_mut().enumerate() {
+            *word = fixed_word(r, pubkey, msg, i * 16 + j);
+        }
+        compress(&mut state, &block);
+    }
+    to_bytes(&state)
+}
 
-    // Round 68
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x06F067AA72176FBA)
-        .wrapping_add(w[68]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+struct Parts<'a> {
+    parts: core::slice::Iter<'a, &'a [u8]>,
+    current: &'a [u8],
+}
 
-    // Round 69
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x0A637DC5A2C898A6)
-        .wrapping_add(w[69]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+impl Parts<'_> {
+    #[inline(always)]
+    fn next_word(&mut self) -> u64 {
+        if self.current.len() >= 8 {
+            let (word, rest) = self.current.split_at(8);
+            self.current = rest;
+            return u64::from_be_bytes(word.try_into().unwrap());
+        }
+        let mut word = [0u8; 8];
+        self.fill(&mut word);
+        u64::from_be_bytes(word)
+    }
 
-    // Round 70
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x113F9804BEF90DAE)
-        .wrapping_add(w[70]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+    // Gathers bytes that straddle part boundaries
+    fn fill(&mut self, out: &mut [u8]) {
+        let mut filled = 0;
+        while filled < out.len() {
+            if self.current.is_empty() {
+                self.current = self.parts.next().unwrap();
+                continue;
+            }
+            let n = self.current.len().min(out.len() - filled);
+            out[filled..filled + n].copy_from_slice(&self.current[..n]);
+            self.current = &self.current[n..];
+            filled += n;
+        }
+    }
+}
 
-    // Round 71
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x1B710B35131C471B)
-        .wrapping_add(w[71]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+/// Computes SHA-512 over the concatenation of `parts`, such as
+/// `&[r, pubkey, msg]`, reading words straight out of each slice.
+pub fn hash_parts(parts: &[&[u8]]) -> [u8; 64] {
+    let len: usize = parts.iter().map(|part| part.len()).sum();
+    let blocks = (len + 16) / 128 + 1;
+    let words = len / 8;
+    let bits = (len as u128) << 3;
+    let mut reader = Parts {
+        parts: parts.iter(),
+        current: &[],
+    };
+    let mut block = [0u64; 16];
+    let mut state = IV;
+    for i in 0..blocks {
+        for (j, word) in block.iter_mut().enumerate() {
+            let k = i * 16 + j;
+            *word = if k < words {
+                reader.next_word()
+            } else if k == blocks * 16 - 1 {
+                bits as u64
+            } else if k == blocks * 16 - 2 {
+                (bits >> 64) as u64
+            } else if k == words {
+                // Trailing bytes followed by the padding bit
+                let mut tail = [0u8; 8];
+                reader.fill(&mut tail[..len % 8]);
+                tail[len % 8] = 0x80;
+                u64::from_be_bytes(tail)
+            } else {
+                0
+            };
+        }
+        compress(&mut state, &block);
+    }
+    to_bytes(&state)
+}
 
-    // Round 72
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x28DB77F523047D84)
-        .wrapping_add(w[72]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{hex, message, Rng};
 
-    // Round 73
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x32CAAB7B40C72493)
-        .wrapping_add(w[73]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+    const R: [u8; 32] = [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30, 31,
+    ];
 
-    // Round 74
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x3C9EBE0A15C9BEBC)
-        .wrapping_add(w[74]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+    // SHA-512 of the bytes 0, 1, ..., 95
+    fn expected() -> [u8; 64] {
+        hex(
+            "ad8ed48e056378b1afcdc0b3d5d3936ac825f96abe0953e9bb85b00ec16084a4\
+             f0bf12a2b0b73f0a29ecb9841a1dc7f003456016203e891aba1bee13ffd19bf0",
+        )
+    }
 
-    // Round 75
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x431D67C49C100D4C)
-        .wrapping_add(w[75]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+    fn concat() -> [u8; 96] {
+        let mut input = [0u8; 96];
+        for (i, byte) in input.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        input
+    }
 
-    // Round 76
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x4CC5D4BECB3E42B6)
-        .wrapping_add(w[76]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+    #[test]
+    fn hash_matches_vector() {
+        let input = concat();
+        let (pubkey, digest) = (
+            input[32..64].try_into().unwrap(),
+            input[64..].try_into().unwrap(),
+        );
+        assert_eq!(hash(&R, pubkey, digest), expected());
+        assert_eq!(hash_concat(&input), expected());
+    }
 
-    // Round 77
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x597F299CFC657E2A)
-        .wrapping_add(w[77]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+    #[test]
+    fn hash_message_matches_sha512() {
+        let pubkey = [0xa5; 32];
+        for len in [0, 1, 31, 32, 33, 47, 48, 111, 112, 175, 176, 300] {
+            let msg = message(len);
+            let mut hasher = Sha512::new();
+            hasher.update(&R);
+            hasher.update(&pubkey);
+            hasher.update(&msg);
+            let expected = hasher.finalize();
+            assert_eq!(hash_message(&R, &pubkey, &msg), expected, "length {len}");
+            assert_eq!(hash_parts(&[&R, &pubkey, &msg]), expected, "length {len}");
+        }
+        let digest = message(32);
+        assert_eq!(
+            hash_message(&R, &[0xa5; 32], &digest),
+            hash(&R, &[0xa5; 32], digest[..].try_into().unwrap())
+        );
+    }
 
-    // Round 78
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x5FCB6FAB3AD6FAEC)
-        .wrapping_add(w[78]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+    fn check_fixed<const N: usize>() {
+        let pubkey = [0xa5; 32];
+        let msg: [u8; N] = message(N).try_into().unwrap();
+        let mut hasher = Sha512::new();
+        hasher.update(&R);
+        hasher.update(&pubkey);
+        hasher.update(&msg);
+        assert_eq!(
+            hash_fixed(&R, &pubkey, &msg),
+            hasher.finalize(),
+            "length {N}"
+        );
+    }
 
-    // Round 79
-    t1 = h
-        .wrapping_add(sigma1(e))
-        .wrapping_add(ch(e, f, g))
-        .wrapping_add(0x6C44198C4A475817)
-        .wrapping_add(w[79]);
-    t2 = sigma0(a).wrapping_add(maj(a, b, c));
-    h = g;
-    g = f;
-    f = e;
-    e = d.wrapping_add(t1);
-    d = c;
-    c = b;
-    b = a;
-    a = t1.wrapping_add(t2);
+    #[test]
+    fn hash_fixed_matches_sha512() {
+        // Either side of the one, two and three block boundaries, and of a
+        // whole final word
+        check_fixed::<0>();
+        check_fixed::<7>();
+        check_fixed::<8>();
+        check_fixed::<32>();
+        check_fixed::<40>();
+        check_fixed::<47>();
+        check_fixed::<48>();
+        check_fixed::<64>();
+        check_fixed::<72>();
+        check_fixed::<111>();
+        check_fixed::<112>();
+        check_fixed::<175>();
+        check_fixed::<176>();
+    }
 
-    // Final state values
-    let state = [
-        0x6a09e667f3bcc908u64.wrapping_add(a).to_be_bytes(),
-        0xbb67ae8584caa73bu64.wrapping_add(b).to_be_bytes(),
-        0x3c6ef372fe94f82bu64.wrapping_add(c).to_be_bytes(),
-        0xa54ff53a5f1d36f1u64.wrapping_add(d).to_be_bytes(),
-        0x510e527fade682d1u64.wrapping_add(e).to_be_bytes(),
-        0x9b05688c2b3e6c1fu64.wrapping_add(f).to_be_bytes(),
-        0x1f83d9abfb41bd6bu64.wrapping_add(g).to_be_bytes(),
-        0x5be0cd19137e2179u64.wrapping_add(h).to_be_bytes(),
-    ];
+    #[test]
+    fn challenge_scalar_matches_wide_reduction() {
+        let mut rng = Rng::new(7);
+        for _ in 0..16 {
+            let (r, pubkey, digest) = (rng.bytes(), rng.bytes(), rng.bytes());
+            assert_eq!(
+                challenge_scalar(&r, &pubkey, &digest),
+                Scalar::from_bytes_mod_order_wide(&hash(&r, &pubkey, &digest)).to_bytes()
+            );
+        }
+    }
 
-    // Convert to bytes
-    let mut result = [0u8; 64];
-    for (i, &word) in state.iter().enumerate() {
-        result[i * 8..(i + 1) * 8].copy_from_slice(&word);
+    #[test]
+    fn slice_entry_points_check_lengths() {
+        let (ok, short, long) = ([7u8; 32], [7u8; 31], [7u8; 33]);
+        let msg = message(10);
+        for bad in [&short[..], &long[..], &[]] {
+            assert_eq!(try_hash(bad, &ok, &ok), Err(Error::InvalidRLength));
+            assert_eq!(try_hash(&ok, bad, &ok), Err(Error::InvalidPublicKeyLength));
+            assert_eq!(try_hash(&ok, &ok, bad), Err(Error::InvalidDigestLength));
+            assert_eq!(
+                try_challenge_scalar(bad, &ok, &ok),
+                Err(Error::InvalidRLength)
+            );
+            assert_eq!(
+                try_challenge_scalar(&ok, bad, &ok),
+                Err(Error::InvalidPublicKeyLength)
+            );
+            assert_eq!(
+                try_challenge_scalar(&ok, &ok, bad),
+                Err(Error::InvalidDigestLength)
+            );
+            assert_eq!(try_hash_message(bad, &ok, &msg), Err(Error::InvalidRLength));
+            assert_eq!(
+                try_hash_message(&ok, bad, &msg),
+                Err(Error::InvalidPublicKeyLength)
+            );
+            assert_eq!(SignatureR::try_from(bad), Err(Error::InvalidRLength));
+            assert_eq!(PublicKey::try_from(bad), Err(Error::InvalidPublicKeyLength));
+            assert_eq!(
+                MessageDigest::try_from(bad),
+                Err(Error::InvalidDigestLength)
+            );
+        }
+        for len in [0, 63, 65] {
+            assert_eq!(
+                Signature::try_from(&[0u8; 65][..len]),
+                Err(Error::InvalidSignatureLength)
+            );
+        }
+        assert_eq!(try_hash(&ok, &ok, &ok), Ok(hash(&ok, &ok, &ok)));
+        assert_eq!(
+            try_challenge_scalar(&ok, &ok, &ok),
+            Ok(challenge_scalar(&ok, &ok, &ok))
+        );
+        assert_eq!(
+            try_hash_message(&ok, &ok, &msg),
+            Ok(hash_message(&ok, &ok, &msg))
+        );
+        assert_eq!(PublicKey::try_from(&ok[..]), Ok(PublicKey::new(ok)));
+        assert!(Signature::try_from(&[0u8; 64][..]).is_ok());
+
+        // Programs return these as custom error codes, so they must not move
+        let codes: [u64; 11] = [
+            Error::InvalidRLength.into(),
+            Error::InvalidPublicKeyLength.into(),
+            Error::InvalidDigestLength.into(),
+            Error::InvalidSignatureLength.into(),
+            VerifyError::InvalidS.into(),
+            VerifyError::InvalidPublicKey.into(),
+            VerifyError::InvalidR.into(),
+            VerifyError::InvalidSignature.into(),
+            StateError::InvalidCounter.into(),
+            StateError::MessageTooShort.into(),
+            StateError::Incomplete.into(),
+        ];
+        assert_eq!(codes, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
     }
 
-    result
+    #[test]
+    fn hash_raw_reads_and_writes_unaligned() {
+        let mut input = [0u8; 97];
+        input[1..].copy_from_slice(&concat());
+        let mut out = [0u8; 66];
+        unsafe { hash_raw(input[1..].as_ptr(), out[1..].as_mut_ptr()) };
+        assert_eq!(out[1..65], expected());
+        assert_eq!((out[0], out[65]), (0, 0));
+    }
 }
@@ -0,0 +1,46 @@
+// Operator impls shared by the field, scalar and point types. Each type
+// implements `&T op &U` itself; these fill in the by-value forms from it.
+
+macro_rules! forward_binop {
+    (impl $trait:ident<$rhs:ty> for $lhs:ty, $method:ident -> $output:ty) => {
+        impl core::ops::$trait<$rhs> for $lhs {
+            type Output = $output;
+
+            #[inline(always)]
+            fn $method(self, rhs: $rhs) -> $output {
+                <&$lhs as core::ops::$trait<&$rhs>>::$method(&self, &rhs)
+            }
+        }
+
+        impl core::ops::$trait<&$rhs> for $lhs {
+            type Output = $output;
+
+            #[inline(always)]
+            fn $method(self, rhs: &$rhs) -> $output {
+                <&$lhs as core::ops::$trait<&$rhs>>::$method(&self, rhs)
+            }
+        }
+
+        impl core::ops::$trait<$rhs> for &$lhs {
+            type Output = $output;
+
+            #[inline(always)]
+            fn $method(self, rhs: $rhs) -> $output {
+                <&$lhs as core::ops::$trait<&$rhs>>::$method(self, &rhs)
+            }
+        }
+    };
+}
+
+macro_rules! forward_neg {
+    ($t:ty) => {
+        impl core::ops::Neg for $t {
+            type Output = $t;
+
+            #[inline(always)]
+            fn neg(self) -> $t {
+                -&self
+            }
+        }
+    };
+}
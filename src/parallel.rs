@@ -0,0 +1,61 @@
+use std::{num::NonZeroUsize, thread};
+
+use crate::hash;
+
+/// Computes [`hash`] for every `(r, pubkey, digest)` in `inputs` on `threads`
+/// scoped worker threads, returning the challenges in input order.
+///
+/// Each worker runs the same scalar [`hash`] as the on-chain program. Pass
+/// [`thread::available_parallelism`] to use every core.
+pub fn hash_parallel(
+    inputs: &[(&[u8; 32], &[u8; 32], &[u8; 32])],
+    threads: NonZeroUsize,
+) -> Vec<[u8; 64]> {
+    let mut result = vec![[0u8; 64]; inputs.len()];
+    if inputs.is_empty() {
+        return result;
+    }
+    let chunk = inputs.len().div_ceil(threads.get());
+    thread::scope(|scope| {
+        for (inputs, result) in inputs.chunks(chunk).zip(result.chunks_mut(chunk)) {
+            scope.spawn(move || {
+                for (out, (r, pubkey, digest)) in result.iter_mut().zip(inputs) {
+                    *out = hash(r, pubkey, digest);
+                }
+            });
+        }
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+
+    #[test]
+    fn matches_hash_in_input_order() {
+        let mut rng = Rng::new(19);
+        let inputs: Vec<([u8; 32], [u8; 32], [u8; 32])> = (0..10)
+            .map(|_| (rng.bytes(), rng.bytes(), rng.bytes()))
+            .collect();
+        // (inputs, threads): uneven splits, more threads than inputs, a single
+        // thread and no inputs at all
+        for (len, threads) in [(10, 3), (10, 4), (7, 2), (3, 8), (10, 1), (1, 1), (0, 4)] {
+            let batch: Vec<_> = inputs[..len]
+                .iter()
+                .map(|(r, pubkey, digest)| (r, pubkey, digest))
+                .collect();
+            let expected: Vec<_> = batch
+                .iter()
+                .map(|(r, pubkey, digest)| hash(r, pubkey, digest))
+                .collect();
+            let threads = NonZeroUsize::new(threads).unwrap();
+            assert_eq!(
+                hash_parallel(&batch, threads),
+                expected,
+                "{len} inputs on {threads} threads"
+            );
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{challenge_words, gamma0, to_bytes, words32};
+
+/// The public key words of the challenge block, with the parts of the message
+/// schedule that only depend on the key computed once.
+///
+/// Use with [`hash_with_prepared`] to hash many signatures by the same signer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PreparedPublicKey {
+    // W4..W7
+    pub(crate) words: [u64; 4],
+    // gamma0(W4), W4 + gamma0(W5), W5 + gamma0(W6) and W6 + gamma0(W7), the key
+    // terms of W19..W22. Only the unrolled schedule consumes them.
+    #[cfg_attr(
+        any(feature = "compact", feature = "u32", target_pointer_width = "32"),
+        allow(dead_code)
+    )]
+    pub(crate) terms: [u64; 4],
+}
+
+impl PreparedPublicKey {
+    #[inline(always)]
+    pub const fn new(pubkey: &[u8; 32]) -> Self {
+        Self::from_words([
+            u64::from_be_bytes([
+                pubkey[0], pubkey[1], pubkey[2], pubkey[3], pubkey[4], pubkey[5], pubkey[6],
+                pubkey[7],
+            ]),
+            u64::from_be_bytes([
+                pubkey[8], pubkey[9], pubkey[10], pubkey[11], pubkey[12], pubkey[13], pubkey[14],
+                pubkey[15],
+            ]),
+            u64::from_be_bytes([
+                pubkey[16], pubkey[17], pubkey[18], pubkey[19], pubkey[20], pubkey[21], pubkey[22],
+                pubkey[23],
+            ]),
+            u64::from_be_bytes([
+                pubkey[24], pubkey[25], pubkey[26], pubkey[27], pubkey[28], pubkey[29], pubkey[30],
+                pubkey[31],
+            ]),
+        ])
+    }
+
+    #[inline(always)]
+    pub(crate) const fn from_words(words: [u64; 4]) -> Self {
+        Self {
+            words,
+            terms: [
+                gamma0(words[0]),
+                words[0].wrapping_add(gamma0(words[1])),
+                words[1].wrapping_add(gamma0(words[2])),
+                words[2].wrapping_add(gamma0(words[3])),
+            ],
+        }
+    }
+}
+
+/// Computes the same challenge as [`crate::hash`], reusing the key-dependent
+/// work in `prepared`.
+#[inline(always)]
+pub fn hash_with_prepared(
+    prepared: &PreparedPublicKey,
+    r: &[u8; 32],
+    digest: &[u8; 32],
+) -> [u8; 64] {
+    let digest = PreparedDigest::from_words(words32(digest));
+    to_bytes(&challenge_words(words32(r), prepared, &digest))
+}
+
+/// The message digest words of the challenge block, with the parts of the
+/// message schedule that only depend on the digest computed once.
+///
+/// Use with [`hash_many_into`] or [`hash_many`] to hash one digest signed by
+/// many keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PreparedDigest {
+    // W8..W11
+    pub(crate) words: [u64; 4],
+    // gamma0(W8), W8 + gamma0(W9), W9 + gamma0(W10) and W10 + gamma0(W11), the
+    // digest terms of W23..W26. Only the unrolled schedule consumes them.
+    #[cfg_attr(
+        any(feature = "compact", feature = "u32", target_pointer_width = "32"),
+        allow(dead_code)
+    )]
+    pub(crate) terms: [u64; 4],
+}
+
+impl PreparedDigest {
+    #[inline(always)]
+    pub const fn new(digest: &[u8; 32]) -> Self {
+        Self::from_words([
+            u64::from_be_bytes([
+                digest[0], digest[1], digest[2], digest[3], digest[4], digest[5], digest[6],
+                digest[7],
+            ]),
+            u64::from_be_bytes([
+                digest[8], digest[9], digest[10], digest[11], digest[12], digest[13], digest[14],
+                digest[15],
+            ]),
+            u64::from_be_bytes([
+                digest[16], digest[17], digest[18], digest[19], digest[20], digest[21], digest[22],
+                digest[23],
+            ]),
+            u64::from_be_bytes([
+                digest[24], digest[25], digest[26], digest[27], digest[28], digest[29], digest[30],
+                digest[31],
+            ]),
+        ])
+    }
+
+    #[inline(always)]
+    pub(crate) const fn from_words(words: [u64; 4]) -> Self {
+        Self {
+            words,
+            terms: [
+                gamma0(words[0]),
+                words[0].wrapping_add(gamma0(words[1])),
+                words[1].wrapping_add(gamma0(words[2])),
+                words[2].wrapping_add(gamma0(words[3])),
+            ],
+        }
+    }
+}
+
+/// Computes the same challenge as [`crate::hash`], reusing the digest-dependent
+/// work in `prepared`.
+#[inline(always)]
+pub fn hash_with_prepared_digest(
+    prepared: &PreparedDigest,
+    r: &[u8; 32],
+    pubkey: &[u8; 32],
+) -> [u8; 64] {
+    let key = PreparedPublicKey::new(pubkey);
+    to_bytes(&challenge_words(words32(r), &key, prepared))
+}
+
+/// Computes the challenge of every `(r, pubkey)` signer over the same digest.
+///
+/// Allocates the result, so on-chain callers should prefer
+/// [`hash_many_into`] over a buffer of their own.
+pub fn hash_many(prepared: &PreparedDigest, signers: &[(&[u8; 32], &[u8; 32])]) -> Vec<[u8; 64]> {
+    let mut out = vec![[0u8; 64]; signers.len()];
+    hash_many_into(prepared, signers, &mut out);
+    out
+}
+
+/// Writes the challenge of every `(r, pubkey)` signer over the same digest into
+/// `out`, without allocating.
+///
+/// # Panics
+///
+/// Panics if `out` is not exactly as long as `signers`.
+pub fn hash_many_into(
+    prepared: &PreparedDigest,
+    signers: &[(&[u8; 32], &[u8; 32])],
+    out: &mut [[u8; 64]],
+) {
+    assert_eq!(
+        out.len(),
+        signers.len(),
+        "output must hold one challenge per signer"
+    );
+    for (out, (r, pubkey)) in out.iter_mut().zip(signers) {
+        *out = hash_with_prepared_digest(prepared, r, pubkey);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash;
+    use crate::testing::Rng;
+
+    #[test]
+    fn prepared_hashes_match_hash() {
+        let mut rng = Rng::new(15);
+        let digest: [u8; 32] = rng.bytes();
+        let signers: Vec<([u8; 32], [u8; 32])> =
+            (0..5).map(|_| (rng.bytes(), rng.bytes())).collect();
+        let signers: Vec<_> = signers.iter().map(|(r, pubkey)| (r, pubkey)).collect();
+        let expected: Vec<_> = signers
+            .iter()
+            .map(|(r, pubkey)| hash(r, pubkey, &digest))
+            .collect();
+
+        let prepared = PreparedDigest::new(&digest);
+        assert_eq!(hash_many(&prepared, &signers), expected);
+        let mut out = [[0u8; 64]; 5];
+        hash_many_into(&prepared, &signers, &mut out);
+        assert_eq!(out[..], expected[..]);
+        hash_many_into(&prepared, &[], &mut []);
+        for ((r, pubkey), expected) in signers.iter().zip(&expected) {
+            let key = PreparedPublicKey::new(pubkey);
+            assert_eq!(hash_with_prepared(&key, r, &digest), *expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "one challenge per signer")]
+    fn hash_many_into_rejects_wrong_length() {
+        let prepared = PreparedDigest::new(&[0; 32]);
+        hash_many_into(&prepared, &[(&[0; 32], &[0; 32])], &mut [[0; 64]; 2]);
+    }
+}
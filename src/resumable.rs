@@ -0,0 +1,199 @@
+use core::cmp::Ordering;
+
+use crate::{Sha512, StateError};
+
+/// Progress of a [`ChallengeState`] through a message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// Message bytes absorbed so far.
+    pub processed: usize,
+    /// Message bytes still to be absorbed.
+    pub remaining: usize,
+}
+
+impl Progress {
+    pub fn is_complete(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+/// SHA-512(R || A || M) state that can be hashed across several transactions.
+///
+/// The state is stored in [`ChallengeState::LEN`] bytes with a fixed layout:
+///
+/// | offset | size | field                                    |
+/// |--------|------|------------------------------------------|
+/// | 0      | 64   | chaining words, little-endian `u64`s     |
+/// | 64     | 8    | bytes absorbed, little-endian `u64`      |
+/// | 72     | 128  | partial block                            |
+///
+/// The byte counter includes R and A, so the message offset is 64 less.
+#[derive(Clone)]
+pub struct ChallengeState(Sha512);
+
+impl ChallengeState {
+    pub const LEN: usize = 200;
+
+    pub fn new(r: &[u8; 32], pubkey: &[u8; 32]) -> Self {
+        let mut hasher = Sha512::new();
+        hasher.update(r);
+        hasher.update(pubkey);
+        Self(hasher)
+    }
+
+    /// Restores a state written by [`ChallengeState::write_to`], rejecting a
+    /// byte counter too small to cover R and A, or whose message offset does
+    /// not fit in a `usize`.
+    pub fn from_bytes(bytes: &[u8; Self::LEN]) -> Result<Self, StateError> {
+        let len = u64::from_le_bytes(bytes[64..72].try_into().unwrap());
+        if len < 64 || usize::try_from(len - 64).is_err() {
+            return Err(StateError::InvalidCounter);
+        }
+        let mut hasher = Sha512::new();
+        for (word, chunk) in hasher.state.iter_mut().zip(bytes[..64].chunks_exact(8)) {
+            *word = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        hasher.len = len;
+        hasher.block.copy_from_slice(&bytes[72..]);
+        Ok(Self(hasher))
+    }
+
+    pub fn write_to(&self, bytes: &mut [u8; Self::LEN]) {
+        for (word, chunk) in self.0.state.iter().zip(bytes[..64].chunks_exact_mut(8)) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        bytes[64..72].copy_from_slice(&self.0.len.to_le_bytes());
+        bytes[72..].copy_from_slice(&self.0.block);
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        self.write_to(&mut bytes);
+        bytes
+    }
+
+    /// Message bytes absorbed so far.
+    pub fn processed(&self) -> usize {
+        (self.0.len - 64) as usize
+    }
+
+    /// Absorbs `msg` from where the previous call stopped, compressing at most
+    /// `max_blocks` blocks.
+    ///
+    /// `msg` must be the same full message on every call. A message shorter
+    /// than what has already been absorbed is rejected.
+    pub fn process_blocks(
+        &mut self,
+        msg: &[u8],
+        max_blocks: usize,
+    ) -> Result<Progress, StateError> {
+        let offset = self.processed();
+        if offset > msg.len() {
+            return Err(StateError::MessageTooShort);
+        }
+        let buffered = (self.0.len % 128) as usize;
+        let budget = max_blocks.saturating_mul(128).saturating_sub(buffered);
+        let rest = &msg[offset..];
+        let take = rest.len().min(budget);
+        self.0.update(&rest[..take]);
+        Ok(Progress {
+            processed: offset + take,
+            remaining: rest.len() - take,
+        })
+    }
+
+    /// Returns the challenge once all of `msg` has been absorbed, and
+    /// [`StateError::Incomplete`] while [`ChallengeState::process_blocks`]
+    /// still has work to do.
+    pub fn finalize(self, msg: &[u8]) -> Result<[u8; 64], StateError> {
+        match self.processed().cmp(&msg.len()) {
+            Ordering::Less => Err(StateError::Incomplete),
+            Ordering::Equal => Ok(self.0.finalize()),
+            Ordering::Greater => Err(StateError::MessageTooShort),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash_message;
+    use crate::testing::message;
+
+    const R: [u8; 32] = [0x11; 32];
+    const PUBKEY: [u8; 32] = [0x22; 32];
+
+    #[test]
+    fn round_trips_between_blocks() {
+        for len in [0, 1, 63, 64, 127, 128, 500, 1000] {
+            let msg = message(len);
+            let mut bytes = ChallengeState::new(&R, &PUBKEY).to_bytes();
+            for max_blocks in [0, 1, 2, 1, 3].into_iter().cycle() {
+                let mut state = ChallengeState::from_bytes(&bytes).unwrap();
+                let progress = state.process_blocks(&msg, max_blocks).unwrap();
+                assert_eq!(progress.processed, state.processed());
+                assert_eq!(progress.processed + progress.remaining, len);
+                bytes = state.to_bytes();
+                if progress.is_complete() {
+                    break;
+                }
+            }
+            let state = ChallengeState::from_bytes(&bytes).unwrap();
+            assert_eq!(state.finalize(&msg), Ok(hash_message(&R, &PUBKEY, &msg)));
+        }
+    }
+
+    #[test]
+    fn rejects_counter_below_prefix() {
+        assert!(matches!(
+            ChallengeState::from_bytes(&[0; ChallengeState::LEN]),
+            Err(StateError::InvalidCounter)
+        ));
+        let mut bytes = ChallengeState::new(&R, &PUBKEY).to_bytes();
+        bytes[64..72].copy_from_slice(&63u64.to_le_bytes());
+        assert!(ChallengeState::from_bytes(&bytes).is_err());
+
+        // 2^32 + 10 used to truncate to 10 on 32-bit targets and underflow
+        let len = (1u64 << 32) + 10;
+        bytes[64..72].copy_from_slice(&len.to_le_bytes());
+        let mut state = ChallengeState::from_bytes(&bytes).unwrap();
+        assert_eq!(state.processed() as u64, len - 64);
+        assert_eq!(
+            state.process_blocks(&[0; 10], 1),
+            Err(StateError::MessageTooShort)
+        );
+
+        // An offset past usize::MAX, only representable on narrower targets
+        if let Some(len) = (usize::MAX as u64).checked_add(65) {
+            bytes[64..72].copy_from_slice(&len.to_le_bytes());
+            assert!(matches!(
+                ChallengeState::from_bytes(&bytes),
+                Err(StateError::InvalidCounter)
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_message_shorter_than_processed() {
+        let msg = message(300);
+        let mut state = ChallengeState::new(&R, &PUBKEY);
+        state.process_blocks(&msg, 2).unwrap();
+        assert_eq!(
+            state.clone().process_blocks(&msg[..10], 1),
+            Err(StateError::MessageTooShort)
+        );
+        assert_eq!(
+            state.clone().finalize(&msg[..10]),
+            Err(StateError::MessageTooShort)
+        );
+        assert_eq!(state.finalize(&msg), Err(StateError::Incomplete));
+
+        let mut bytes = [0; ChallengeState::LEN];
+        bytes[64..72].copy_from_slice(&(u32::MAX as u64 + 64).to_le_bytes());
+        let mut state = ChallengeState::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            state.process_blocks(&msg, 1),
+            Err(StateError::MessageTooShort)
+        );
+    }
+}
@@ -0,0 +1,160 @@
+// Generators for the SHA-512 compression rounds (FIPS 180-4 §6.4.2), driven by
+// the round-constant table `K`.
+
+// One round over the working variables `[a, b, c, d, e, f, g, h]`, where `$kw`
+// is K[t] + W[t]
+macro_rules! round {
+    ([$a:ident, $b:ident, $c:ident, $d:ident, $e:ident, $f:ident, $g:ident, $h:ident], $kw:expr) => {{
+        let t1 = $h
+            .wrapping_add(sigma1($e))
+            .wrapping_add(ch($e, $f, $g))
+            .wrapping_add($kw);
+        let t2 = sigma0($a).wrapping_add(maj($a, $b, $c));
+        $h = $g;
+        $g = $f;
+        $f = $e;
+        $e = $d.wrapping_add(t1);
+        $d = $c;
+        $c = $b;
+        $b = $a;
+        $a = t1.wrapping_add(t2);
+    }};
+}
+
+// Expands W[t] in place in the rolling 16-word window `$w`
+macro_rules! schedule {
+    ($w:ident, $t:expr) => {
+        $w[$t & 15] = $w[$t & 15]
+            .wrapping_add(gamma0($w[($t + 1) & 15]))
+            .wrapping_add($w[($t + 9) & 15])
+            .wrapping_add(gamma1($w[($t + 14) & 15]));
+    };
+}
+
+// Runs rounds `$start..$end`, `$unroll` rounds per loop iteration. An unroll
+// factor of 80 emits every round straight-line with constant indices; the
+// others must divide `$end - $start`.
+macro_rules! rounds {
+    ($w:ident, $s:tt, $start:literal, $end:literal, 80) => {
+        rounds!(@unrolled $w, $s, $start, $end, [0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63 64 65 66 67 68 69 70 71 72 73 74 75 76 77 78 79])
+    };
+    ($w:ident, $s:tt, $start:literal, $end:literal, 16) => {
+        rounds!(@loop $w, $s, $start, $end, [0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15])
+    };
+    ($w:ident, $s:tt, $start:literal, $end:literal, 8) => {
+        rounds!(@loop $w, $s, $start, $end, [0 1 2 3 4 5 6 7])
+    };
+    ($w:ident, $s:tt, $start:literal, $end:literal, 4) => {
+        rounds!(@loop $w, $s, $start, $end, [0 1 2 3])
+    };
+    ($w:ident, $s:tt, $start:literal, $end:literal, 2) => {
+        rounds!(@loop $w, $s, $start, $end, [0 1])
+    };
+    ($w:ident, $s:tt, $start:literal, $end:literal, 1) => {
+        rounds!(@loop $w, $s, $start, $end, [0])
+    };
+    (@unrolled $w:ident, $s:tt, $start:literal, $end:literal, [$($t:literal)*]) => {
+        $(
+            if $t >= $start && $t < $end {
+                if $t >= 16 {
+                    schedule!($w, $t);
+                }
+                round!($s, K[$t].wrapping_add($w[$t & 15]));
+            }
+        )*
+    };
+    (@loop $w:ident, $s:tt, $start:literal, $end:literal, [$($offset:literal)*]) => {{
+        let mut i = $start;
+        while i < $end {
+            $(
+                let t = i + $offset;
+                if t >= 16 {
+                    schedule!($w, t);
+                }
+                round!($s, K[t].wrapping_add($w[t & 15]));
+            )*
+            i += [$($offset),*].len();
+        }
+    }};
+}
+
+// Runs rounds `$start..$end` of the compact builds, with the unroll factor
+// picked by the `unroll-*` features: 1 when none is enabled, the largest when
+// several are
+#[cfg(feature = "compact")]
+macro_rules! rolled_rounds {
+    ($w:ident, $s:tt, $start:literal, $end:literal) => {
+        #[cfg(feature = "unroll-16")]
+        rounds!($w, $s, $start, $end, 16);
+        #[cfg(all(feature = "unroll-8", not(feature = "unroll-16")))]
+        rounds!($w, $s, $start, $end, 8);
+        #[cfg(all(
+            feature = "unroll-4",
+            not(any(feature = "unroll-8", feature = "unroll-16"))
+        ))]
+        rounds!($w, $s, $start, $end, 4);
+        #[cfg(all(
+            feature = "unroll-2",
+            not(any(feature = "unroll-4", feature = "unroll-8", feature = "unroll-16"))
+        ))]
+        rounds!($w, $s, $start, $end, 2);
+        #[cfg(not(any(
+            feature = "unroll-2",
+            feature = "unroll-4",
+            feature = "unroll-8",
+            feature = "unroll-16"
+        )))]
+        rounds!($w, $s, $start, $end, 1);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::Rng;
+    use crate::{ch, compress, gamma0, gamma1, maj, sigma0, sigma1, K};
+
+    // A compression function running rounds 0..32 and 32..80 separately with
+    // the given unroll factor
+    macro_rules! compress_with {
+        ($name:ident, $unroll:tt) => {
+            fn $name(state: &[u64; 8], block: &[u64; 16]) -> [u64; 8] {
+                let mut w = *block;
+                let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+                rounds!(w, [a, b, c, d, e, f, g, h], 0, 32, $unroll);
+                rounds!(w, [a, b, c, d, e, f, g, h], 32, 80, $unroll);
+                let mut result = *state;
+                for (word, x) in result.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+                    *word = word.wrapping_add(x);
+                }
+                result
+            }
+        };
+    }
+
+    compress_with!(compress_1, 1);
+    compress_with!(compress_2, 2);
+    compress_with!(compress_4, 4);
+    compress_with!(compress_8, 8);
+    compress_with!(compress_16, 16);
+    compress_with!(compress_80, 80);
+
+    #[test]
+    fn every_unroll_factor_matches_compress() {
+        let mut rng = Rng::new(17);
+        for _ in 0..8 {
+            let (state, block) = (rng.words(), rng.words());
+            let mut expected = state;
+            compress(&mut expected, &block);
+            for with_factor in [
+                compress_1,
+                compress_2,
+                compress_4,
+                compress_8,
+                compress_16,
+                compress_80,
+            ] {
+                assert_eq!(with_factor(&state, &block), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,451 @@
+// Integers modulo the group order L = 2^252 + 27742317777372353535851937790883648493.
+//
+// Values are split into 21-bit limbs held in i64s, so every product fits in a
+// native 64-bit multiply and sBPF never has to emulate u128 arithmetic.
+
+use core::ops::{Add, Mul, Neg, Sub};
+
+// L as little-endian bytes
+pub(crate) const L: [u8; 32] = [
+    0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+];
+
+// 2^252 = -27742317777372353535851937790883648493 (mod L), in 21-bit limbs
+const FOLD: [i64; 6] = [666643, 470296, 654183, -997805, 136657, -683901];
+
+#[inline(always)]
+fn fold(s: &mut [i64; 24], i: usize) {
+    for (j, c) in FOLD.iter().enumerate() {
+        s[i - 12 + j] += s[i] * c;
+    }
+    s[i] = 0;
+}
+
+// Carries into the next limb, leaving s[i] in [-2^20, 2^20)
+#[inline(always)]
+fn carry_signed(s: &mut [i64; 24], i: usize) {
+    let carry = (s[i] + (1 << 20)) >> 21;
+    s[i + 1] += carry;
+    s[i] -= carry << 21;
+}
+
+// Carries into the next limb, leaving s[i] in [0, 2^21)
+#[inline(always)]
+fn carry(s: &mut [i64; 24], i: usize) {
+    let carry = s[i] >> 21;
+    s[i + 1] += carry;
+    s[i] -= carry << 21;
+}
+
+// Splits a little-endian integer into 21-bit limbs, the last one taking all the
+// remaining bits
+#[inline(always)]
+fn split<const N: usize, const M: usize>(x: &[u64; N]) -> [i64; M] {
+    let mut s = [0i64; M];
+    for (i, limb) in s.iter_mut().enumerate().take(M - 1) {
+        let (word, shift) = (21 * i / 64, 21 * i % 64);
+        let mut bits = x[word] >> shift;
+        if shift > 43 {
+            bits |= x[word + 1] << (64 - shift);
+        }
+        *limb = (bits & 0x1fffff) as i64;
+    }
+    s[M - 1] = (x[N - 1] >> (21 * (M - 1) - 64 * (N - 1))) as i64;
+    s
+}
+
+/// Reduces a 512-bit little-endian integer, given as 64-bit limbs, modulo L.
+#[inline(always)]
+pub(crate) fn reduce_wide(x: &[u64; 8]) -> [u8; 32] {
+    reduce(split(x))
+}
+
+// Reduces a non-negative integer below 2^512, given as 21-bit limbs within
+// about 2^21 of zero, modulo L
+#[inline(always)]
+fn reduce(mut s: [i64; 24]) -> [u8; 32] {
+    for i in (18..24).rev() {
+        fold(&mut s, i);
+    }
+    for i in (6..17).step_by(2) {
+        carry_signed(&mut s, i);
+    }
+    for i in (7..16).step_by(2) {
+        carry_signed(&mut s, i);
+    }
+    for i in (12..18).rev() {
+        fold(&mut s, i);
+    }
+    for i in (0..11).step_by(2) {
+        carry_signed(&mut s, i);
+    }
+    for i in (1..12).step_by(2) {
+        carry_signed(&mut s, i);
+    }
+    fold(&mut s, 12);
+    for i in 0..12 {
+        carry(&mut s, i);
+    }
+    fold(&mut s, 12);
+    for i in 0..11 {
+        carry(&mut s, i);
+    }
+
+    // Pack the twelve 21-bit limbs into 256 bits
+    let mut limbs = [0u64; 4];
+    for (i, &limb) in s.iter().enumerate().take(12) {
+        let (word, shift) = (21 * i / 64, 21 * i % 64);
+        limbs[word] |= (limb as u64) << shift;
+        if shift > 43 && word < 3 {
+            limbs[word + 1] |= (limb as u64) >> (64 - shift);
+        }
+    }
+    let mut result = [0u8; 32];
+    for (chunk, limb) in result.chunks_exact_mut(8).zip(limbs) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    result
+}
+
+/// Whether a little-endian scalar is fully reduced, i.e. less than L.
+fn is_canonical(s: &[u8; 32]) -> bool {
+    for (&byte, l) in s.iter().zip(L).rev() {
+        if byte != l {
+            return byte < l;
+        }
+    }
+    false
+}
+
+#[inline(always)]
+fn words<const N: usize>(bytes: &[u8]) -> [u64; N] {
+    let mut words = [0u64; N];
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(8)) {
+        *word = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    words
+}
+
+/// An integer modulo the group order L, held as its canonical little-endian
+/// encoding.
+///
+/// Arithmetic runs in constant time; only [`Scalar::from_canonical_bytes`]
+/// branches on its input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Scalar([u8; 32]);
+
+impl Scalar {
+    pub const ZERO: Self = Self([0; 32]);
+    pub const ONE: Self = {
+        let mut bytes = [0; 32];
+        bytes[0] = 1;
+        Self(bytes)
+    };
+
+    /// Reduces a 64-byte little-endian integer, such as the output of
+    /// [`crate::hash`], modulo L.
+    pub fn from_bytes_mod_order_wide(bytes: &[u8; 64]) -> Self {
+        Self(reduce_wide(&words(bytes)))
+    }
+
+    /// Reduces a 32-byte little-endian integer modulo L.
+    pub fn from_bytes_mod_order(bytes: &[u8; 32]) -> Self {
+        let mut s = [0i64; 24];
+        s[..12].copy_from_slice(&split::<4, 12>(&words(bytes)));
+        Self(reduce(s))
+    }
+
+    /// Decodes a little-endian integer, rejecting values >= L such as a
+    /// malleated signature S.
+    pub fn from_canonical_bytes(bytes: &[u8; 32]) -> Option<Self> {
+        is_canonical(bytes).then_some(Self(*bytes))
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    pub const fn to_bytes(self) -> [u8; 32] {
+        self.0
+    }
+
+    #[inline(always)]
+    fn limbs(&self) -> [i64; 12] {
+        split(&words::<4>(&self.0))
+    }
+
+    /// Computes 1/a as a^(L - 2). Zero maps to zero.
+    pub fn invert(&self) -> Self {
+        let mut exponent = L;
+        exponent[0] -= 2;
+        let mut result = Self::ONE;
+        for i in (0..253).rev() {
+            result = result * result;
+            if exponent[i / 8] >> (i % 8) & 1 == 1 {
+                result = result * self;
+            }
+        }
+        result
+    }
+}
+
+impl Add<&Scalar> for &Scalar {
+    type Output = Scalar;
+
+    fn add(self, other: &Scalar) -> Scalar {
+        let (a, b) = (self.limbs(), other.limbs());
+        let mut s = [0i64; 24];
+        for i in 0..12 {
+            s[i] = a[i] + b[i];
+        }
+        Scalar(reduce(s))
+    }
+}
+
+impl Sub<&Scalar> for &Scalar {
+    type Output = Scalar;
+
+    fn sub(self, other: &Scalar) -> Scalar {
+        // a - b + L keeps the value non-negative
+        let (a, b, l) = (self.limbs(), other.limbs(), Scalar(L).limbs());
+        let mut s = [0i64; 24];
+        for i in 0..12 {
+            s[i] = a[i] - b[i] + l[i];
+        }
+        Scalar(reduce(s))
+    }
+}
+
+impl Mul<&Scalar> for &Scalar {
+    type Output = Scalar;
+
+    fn mul(self, other: &Scalar) -> Scalar {
+        let (a, b) = (self.limbs(), other.limbs());
+        let mut s = [0i64; 24];
+        for i in 0..12 {
+            for j in 0..12 {
+                s[i + j] += a[i] * b[j];
+            }
+        }
+        for i in (0..23).step_by(2) {
+            carry_signed(&mut s, i);
+        }
+        for i in (1..22).step_by(2) {
+            carry_signed(&mut s, i);
+        }
+        Scalar(reduce(s))
+    }
+}
+
+impl Neg for &Scalar {
+    type Output = Scalar;
+
+    fn neg(self) -> Scalar {
+        Scalar::ZERO - self
+    }
+}
+
+forward_binop!(impl Add<Scalar> for Scalar, add -> Scalar);
+forward_binop!(impl Sub<Scalar> for Scalar, sub -> Scalar);
+forward_binop!(impl Mul<Scalar> for Scalar, mul -> Scalar);
+forward_neg!(Scalar);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+
+    // Reference arithmetic on little-endian u64 words by shift-and-subtract
+    // reduction modulo L, sharing nothing with the limb code above
+    type Int = [u64; 4];
+
+    const L_INT: Int = [
+        0x5812631a5cf5d3ed,
+        0x14def9dea2f79cd6,
+        0x0000000000000000,
+        0x1000000000000000,
+    ];
+
+    fn geq(a: &Int, b: &Int) -> bool {
+        for i in (0..4).rev() {
+            if a[i] != b[i] {
+                return a[i] > b[i];
+            }
+        }
+        true
+    }
+
+    fn sub_in_place(a: &mut Int, b: &Int) {
+        let mut borrow = false;
+        for i in 0..4 {
+            let (x, b1) = a[i].overflowing_sub(b[i]);
+            let (x, b2) = x.overflowing_sub(borrow as u64);
+            a[i] = x;
+            borrow = b1 | b2;
+        }
+    }
+
+    // Reduces an integer of any number of words modulo L, one bit at a time
+    fn modl(x: &[u64]) -> Int {
+        let mut r = [0u64; 4];
+        for i in (0..x.len() * 64).rev() {
+            // r < L < 2^253, so shifting left cannot overflow
+            for j in (1..4).rev() {
+                r[j] = r[j] << 1 | r[j - 1] >> 63;
+            }
+            r[0] = r[0] << 1 | (x[i / 64] >> (i % 64) & 1);
+            if geq(&r, &L_INT) {
+                sub_in_place(&mut r, &L_INT);
+            }
+        }
+        r
+    }
+
+    fn ref_add(a: &Int, b: &Int) -> Int {
+        let mut sum = [0u64; 5];
+        let mut carry = 0u128;
+        for i in 0..4 {
+            carry += a[i] as u128 + b[i] as u128;
+            sum[i] = carry as u64;
+            carry >>= 64;
+        }
+        sum[4] = carry as u64;
+        modl(&sum)
+    }
+
+    fn ref_neg(a: &Int) -> Int {
+        let mut r = L_INT;
+        sub_in_place(&mut r, &modl(a));
+        modl(&r)
+    }
+
+    fn ref_sub(a: &Int, b: &Int) -> Int {
+        ref_add(a, &ref_neg(b))
+    }
+
+    fn ref_mul(a: &Int, b: &Int) -> Int {
+        let mut product = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                carry += a[i] as u128 * b[j] as u128 + product[i + j] as u128;
+                product[i + j] = carry as u64;
+                carry >>= 64;
+            }
+            product[i + 4] = carry as u64;
+        }
+        modl(&product)
+    }
+
+    fn ref_pow(a: &Int, e: &Int) -> Int {
+        let mut r = [1, 0, 0, 0];
+        for i in (0..256).rev() {
+            r = ref_mul(&r, &r);
+            if e[i / 64] >> (i % 64) & 1 == 1 {
+                r = ref_mul(&r, a);
+            }
+        }
+        r
+    }
+
+    fn to_bytes<const N: usize>(a: &[u64]) -> [u8; N] {
+        let mut bytes = [0u8; N];
+        for (chunk, word) in bytes.chunks_exact_mut(8).zip(a) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+
+    fn scalar(a: &Int) -> Scalar {
+        Scalar::from_bytes_mod_order(&to_bytes(a))
+    }
+
+    fn check(x: &Scalar, expected: &Int) {
+        assert_eq!(x.to_bytes(), to_bytes::<32>(&modl(expected)));
+    }
+
+    // 0, 1, 2, L - 2, L - 1, L, L + 1, 2L, 2^252, 2^255 and 2^256 - 1
+    fn edge_values() -> Vec<Int> {
+        let mut values = vec![[0; 4], [1, 0, 0, 0], [2, 0, 0, 0]];
+        for delta in [-2i64, -1, 0, 1] {
+            let mut x = L_INT;
+            x[0] = x[0].wrapping_add(delta as u64);
+            values.push(x);
+        }
+        let mut double = [0u64; 4];
+        for i in 0..4 {
+            double[i] = L_INT[i] << 1 | if i > 0 { L_INT[i - 1] >> 63 } else { 0 };
+        }
+        values.push(double);
+        values.push([0, 0, 0, 1 << 60]);
+        values.push([0, 0, 0, 1 << 63]);
+        values.push([u64::MAX; 4]);
+        values
+    }
+
+    #[test]
+    fn decoding_matches_reference() {
+        let mut rng = Rng::new(27);
+        let mut values = edge_values();
+        values.extend((0..8).map(|_| rng.words()));
+        for a in &values {
+            let bytes = to_bytes(a);
+            check(&Scalar::from_bytes_mod_order(&bytes), a);
+            let canonical = !geq(a, &L_INT);
+            assert_eq!(
+                Scalar::from_canonical_bytes(&bytes),
+                canonical.then_some(Scalar(bytes))
+            );
+        }
+        assert_eq!(to_bytes::<32>(&L_INT), L);
+
+        let mut wide = vec![[0u64; 8], [u64::MAX; 8]];
+        for a in &edge_values() {
+            let mut high = [0u64; 8];
+            high[4..].copy_from_slice(a);
+            wide.push(high);
+            let mut low = [0u64; 8];
+            low[..4].copy_from_slice(a);
+            wide.push(low);
+        }
+        wide.extend((0..8).map(|_| rng.words()));
+        for x in &wide {
+            check(&Scalar::from_bytes_mod_order_wide(&to_bytes(x)), &modl(x));
+        }
+    }
+
+    #[test]
+    fn arithmetic_matches_reference() {
+        let mut rng = Rng::new(28);
+        let mut values = edge_values();
+        values.extend((0..8).map(|_| rng.words()));
+        for a in &values {
+            let x = scalar(a);
+            check(&-x, &ref_neg(a));
+            for b in &values {
+                let y = scalar(b);
+                check(&(x + y), &ref_add(a, b));
+                check(&(x - y), &ref_sub(a, b));
+                check(&(x * y), &ref_mul(a, b));
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn invert_matches_reference() {
+        let mut rng = Rng::new(29);
+        let mut l_minus_2 = L_INT;
+        l_minus_2[0] -= 2;
+        let mut values = edge_values();
+        values.extend((0..4).map(|_| rng.words()));
+        for a in &values {
+            let x = scalar(a);
+            check(&x.invert(), &ref_pow(a, &l_minus_2));
+            if x != Scalar::ZERO {
+                assert_eq!(x * x.invert(), Scalar::ONE);
+            }
+        }
+    }
+}
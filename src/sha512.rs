@@ -0,0 +1,312 @@
+use crate::{compress, to_bytes, IV};
+
+#[inline(always)]
+fn compress_bytes(state: &mut [u64; 8], bytes: &[u8; 128]) {
+    let mut block = [0u64; 16];
+    for (word, chunk) in block.iter_mut().zip(bytes.chunks_exact(8)) {
+        *word = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+    compress(state, &block);
+}
+
+/// Incremental SHA-512 hasher driven by the same compression as [`crate::hash`].
+///
+/// Cloning a hasher captures its midstate, so a shared prefix only has to be
+/// absorbed once.
+#[derive(Clone)]
+pub struct Sha512 {
+    pub(crate) state: [u64; 8],
+    pub(crate) block: [u8; 128],
+    pub(crate) len: u64,
+}
+
+impl Default for Sha512 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha512 {
+    pub const fn new() -> Self {
+        Self::with_iv(IV)
+    }
+
+    const fn with_iv(iv: [u64; 8]) -> Self {
+        Self {
+            state: iv,
+            block: [0; 128],
+            len: 0,
+        }
+    }
+
+    /// One-shot SHA-512 of `data`.
+    pub fn digest(data: &[u8]) -> [u8; 64] {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        let offset = (self.len % 128) as usize;
+        self.len += data.len() as u64;
+        if offset > 0 {
+            let take = data.len().min(128 - offset);
+            self.block[offset..offset + take].copy_from_slice(&data[..take]);
+            if offset + take < 128 {
+                return;
+            }
+            compress_bytes(&mut self.state, &self.block);
+            data = &data[take..];
+        }
+        let mut blocks = data.chunks_exact(128);
+        for block in &mut blocks {
+            compress_bytes(&mut self.state, block.try_into().unwrap());
+        }
+        let rest = blocks.remainder();
+        self.block[..rest.len()].copy_from_slice(rest);
+    }
+
+    pub fn finalize(self) -> [u8; 64] {
+        to_bytes(&self.finalize_state())
+    }
+
+    fn finalize_state(mut self) -> [u64; 8] {
+        let offset = (self.len % 128) as usize;
+        self.block[offset] = 0x80;
+        self.block[offset + 1..].fill(0);
+        if offset >= 112 {
+            compress_bytes(&mut self.state, &self.block);
+            self.block.fill(0);
+        }
+        let bits = (self.len as u128) << 3;
+        self.block[112..].copy_from_slice(&bits.to_be_bytes());
+        compress_bytes(&mut self.state, &self.block);
+        self.state
+    }
+}
+
+const SHA384_IV: [u64; 8] = [
+    0xcbbb9d5dc1059ed8,
+    0x629a292a367cd507,
+    0x9159015a3070dd17,
+    0x152fecd8f70e5939,
+    0x67332667ffc00b31,
+    0x8eb44a8768581511,
+    0xdb0c2e0d64f98fa7,
+    0x47b5481dbefa4fa4,
+];
+
+const SHA512_224_IV: [u64; 8] = [
+    0x8c3d37c819544da2,
+    0x73e1996689dcd4d6,
+    0x1dfab7ae32ff9c82,
+    0x679dd514582f9fcf,
+    0x0f6d2b697bd44da8,
+    0x77e36f7304c48942,
+    0x3f9d85a86a1d36c8,
+    0x1112e6ad91d692a1,
+];
+
+const SHA512_256_IV: [u64; 8] = [
+    0x22312194fc2bf72c,
+    0x9f555fa3c84c64c2,
+    0x2393b86b6f53b151,
+    0x963877195940eabd,
+    0x96283ee2a88effe3,
+    0xbe5e1e2553863992,
+    0x2b0199fc2c85b8aa,
+    0x0eb72ddc81c52ca2,
+];
+
+macro_rules! truncated {
+    ($(#[$doc:meta])* $name:ident, $iv:expr, $len:literal) => {
+        $(#[$doc])*
+        #[derive(Clone)]
+        pub struct $name(Sha512);
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl $name {
+            pub const fn new() -> Self {
+                Self(Sha512::with_iv($iv))
+            }
+
+            pub fn digest(data: &[u8]) -> [u8; $len] {
+                let mut hasher = Self::new();
+                hasher.update(data);
+                hasher.finalize()
+            }
+
+            pub fn update(&mut self, data: &[u8]) {
+                self.0.update(data)
+            }
+
+            pub fn finalize(self) -> [u8; $len] {
+                let mut result = [0u8; $len];
+                result.copy_from_slice(&to_bytes(&self.0.finalize_state())[..$len]);
+                result
+            }
+        }
+    };
+}
+
+truncated!(
+    /// SHA-384 (FIPS 180-4 §6.5).
+    Sha384,
+    SHA384_IV,
+    48
+);
+
+truncated!(
+    /// SHA-512/224 (FIPS 180-4 §6.6).
+    Sha512_224,
+    SHA512_224_IV,
+    28
+);
+
+truncated!(
+    /// SHA-512/256 (FIPS 180-4 §6.7).
+    Sha512_256,
+    SHA512_256_IV,
+    32
+);
+
+/// Generates the SHA-512/t initial hash value (FIPS 180-4 §5.3.6).
+///
+/// # Panics
+///
+/// Panics if `t` is 0, 384 or not below 512.
+pub fn sha512_t_iv(t: usize) -> [u64; 8] {
+    assert!(t > 0 && t < 512 && t != 384, "invalid SHA-512/t length");
+    let mut iv = IV;
+    for word in iv.iter_mut() {
+        *word ^= 0xa5a5a5a5a5a5a5a5;
+    }
+    // "SHA-512/" followed by t in decimal
+    let mut name = *b"SHA-512/000";
+    let digits = if t >= 100 {
+        3
+    } else if t >= 10 {
+        2
+    } else {
+        1
+    };
+    let mut n = t;
+    for digit in name[8..8 + digits].iter_mut().rev() {
+        *digit = b'0' + (n % 10) as u8;
+        n /= 10;
+    }
+    let mut hasher = Sha512::with_iv(iv);
+    hasher.update(&name[..8 + digits]);
+    hasher.finalize_state()
+}
+
+/// SHA-512/t for any byte-aligned output length `t` in bits.
+#[derive(Clone)]
+pub struct Sha512Trunc {
+    inner: Sha512,
+    t: usize,
+}
+
+impl Sha512Trunc {
+    /// # Panics
+    ///
+    /// Panics if `t` is not a multiple of 8 or is not a valid SHA-512/t length.
+    pub fn new(t: usize) -> Self {
+        assert!(
+            t & 7 == 0,
+            "SHA-512/t length must be a whole number of bytes"
+        );
+        Self {
+            inner: Sha512::with_iv(sha512_t_iv(t)),
+            t,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data)
+    }
+
+    /// Writes the `t / 8` byte digest into `out`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is not exactly `t / 8` bytes long.
+    pub fn finalize_into(self, out: &mut [u8]) {
+        out.copy_from_slice(&to_bytes(&self.inner.finalize_state())[..self.t / 8]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::hex;
+
+    // FIPS 180-2 examples C.1 and C.2
+    const ABC: &[u8] = b"abc";
+    const TWO_BLOCK: &[u8] = b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmn\
+        hijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+
+    #[test]
+    fn matches_fips_vectors() {
+        let cases: [(&[u8], [u8; 64]); 3] = [
+            (
+                b"",
+                hex(
+                    "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+                     47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+                ),
+            ),
+            (
+                ABC,
+                hex(
+                    "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+                     2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+                ),
+            ),
+            (
+                TWO_BLOCK,
+                hex(
+                    "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018\
+                     501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909",
+                ),
+            ),
+        ];
+        for (data, expected) in cases {
+            assert_eq!(Sha512::digest(data), expected);
+            let mut hasher = Sha512::new();
+            for byte in data {
+                hasher.update(core::slice::from_ref(byte));
+            }
+            assert_eq!(hasher.finalize(), expected);
+        }
+    }
+
+    #[test]
+    fn truncated_variants_match_fips_vectors() {
+        assert_eq!(
+            Sha384::digest(ABC),
+            hex(
+                "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed\
+                 8086072ba1e7cc2358baeca134c825a7"
+            )
+        );
+        assert_eq!(
+            Sha512_256::digest(ABC),
+            hex("53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23")
+        );
+        assert_eq!(
+            Sha512_224::digest(ABC),
+            hex("4634270f707b6a54daae7530460842e20e37ed265ceee9a43e8924aa")
+        );
+        let mut out = [0u8; 32];
+        let mut hasher = Sha512Trunc::new(256);
+        hasher.update(ABC);
+        hasher.finalize_into(&mut out);
+        assert_eq!(out, Sha512_256::digest(ABC));
+    }
+}
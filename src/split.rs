@@ -0,0 +1,238 @@
+// SHA-512 compression over 64-bit words held as pairs of u32 halves, for
+// targets such as Cortex-M where u64 arithmetic is emulated. Adds propagate the
+// carry out of the low half and rotates are split across the two halves; the
+// rounds themselves come from the same generator as the u64 backends.
+
+use crate::{PreparedDigest, PreparedPublicKey};
+
+#[derive(Clone, Copy)]
+struct Word {
+    hi: u32,
+    lo: u32,
+}
+
+impl Word {
+    const fn new(x: u64) -> Self {
+        Self {
+            hi: (x >> 32) as u32,
+            lo: x as u32,
+        }
+    }
+
+    const fn get(self) -> u64 {
+        ((self.hi as u64) << 32) | self.lo as u64
+    }
+
+    #[inline(always)]
+    const fn wrapping_add(self, rhs: Self) -> Self {
+        let (lo, carry) = self.lo.overflowing_add(rhs.lo);
+        Self {
+            hi: self.hi.wrapping_add(rhs.hi).wrapping_add(carry as u32),
+            lo,
+        }
+    }
+}
+
+const K: [Word; 80] = {
+    let mut k = [Word::new(0); 80];
+    let mut i = 0;
+    while i < 80 {
+        k[i] = Word::new(crate::K[i]);
+        i += 1;
+    }
+    k
+};
+
+// Rotates right by 0 < n < 64 with n != 32
+#[inline(always)]
+const fn rotr(x: Word, n: u32) -> Word {
+    let (hi, lo, n) = if n < 32 {
+        (x.hi, x.lo, n)
+    } else {
+        (x.lo, x.hi, n - 32)
+    };
+    Word {
+        hi: (hi >> n) | (lo << (32 - n)),
+        lo: (lo >> n) | (hi << (32 - n)),
+    }
+}
+
+// Shifts right by 0 < n < 32
+#[inline(always)]
+const fn shr(x: Word, n: u32) -> Word {
+    Word {
+        hi: x.hi >> n,
+        lo: (x.lo >> n) | (x.hi << (32 - n)),
+    }
+}
+
+#[inline(always)]
+const fn xor(x: Word, y: Word) -> Word {
+    Word {
+        hi: x.hi ^ y.hi,
+        lo: x.lo ^ y.lo,
+    }
+}
+
+#[inline(always)]
+const fn gamma0(x: Word) -> Word {
+    xor(xor(rotr(x, 1), rotr(x, 8)), shr(x, 7))
+}
+
+#[inline(always)]
+const fn gamma1(x: Word) -> Word {
+    xor(xor(rotr(x, 19), rotr(x, 61)), shr(x, 6))
+}
+
+#[inline(always)]
+const fn ch(x: Word, y: Word, z: Word) -> Word {
+    Word {
+        hi: (x.hi & y.hi) ^ (!x.hi & z.hi),
+        lo: (x.lo & y.lo) ^ (!x.lo & z.lo),
+    }
+}
+
+#[inline(always)]
+const fn maj(x: Word, y: Word, z: Word) -> Word {
+    Word {
+        hi: (x.hi & y.hi) ^ (x.hi & z.hi) ^ (y.hi & z.hi),
+        lo: (x.lo & y.lo) ^ (x.lo & z.lo) ^ (y.lo & z.lo),
+    }
+}
+
+#[inline(always)]
+const fn sigma0(x: Word) -> Word {
+    xor(xor(rotr(x, 28), rotr(x, 34)), rotr(x, 39))
+}
+
+#[inline(always)]
+const fn sigma1(x: Word) -> Word {
+    xor(xor(rotr(x, 14), rotr(x, 18)), rotr(x, 41))
+}
+
+// Cross-checks every split primitive against its u64 counterpart at compile time
+const _: () = {
+    let samples = [
+        0,
+        1,
+        u64::MAX,
+        0x8000000000000000,
+        0x00000000ffffffff,
+        0x0123456789abcdef,
+        crate::IV[0],
+        crate::K[79],
+    ];
+    let mut i = 0;
+    while i < samples.len() {
+        let (x, y, z) = (
+            samples[i],
+            samples[(i + 1) % samples.len()],
+            samples[(i + 2) % samples.len()],
+        );
+        let (wx, wy, wz) = (Word::new(x), Word::new(y), Word::new(z));
+        assert!(wx.wrapping_add(wy).get() == x.wrapping_add(y));
+        assert!(gamma0(wx).get() == crate::gamma0(x));
+        assert!(gamma1(wx).get() == crate::gamma1(x));
+        assert!(sigma0(wx).get() == crate::sigma0(x));
+        assert!(sigma1(wx).get() == crate::sigma1(x));
+        assert!(ch(wx, wy, wz).get() == crate::ch(x, y, z));
+        assert!(maj(wx, wy, wz).get() == crate::maj(x, y, z));
+        i += 1;
+    }
+};
+
+const fn words<const N: usize>(x: &[u64; N]) -> [Word; N] {
+    let mut words = [Word::new(0); N];
+    let mut i = 0;
+    while i < N {
+        words[i] = Word::new(x[i]);
+        i += 1;
+    }
+    words
+}
+
+// Kept out of line even when unrolled: split rounds are about twice the code
+// of u64 ones, and inlining them into every caller swamps the optimizer
+#[inline(never)]
+const fn rounds(state: &[u64; 8], block: &[u64; 16]) -> [u64; 8] {
+    let mut w = words(block);
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = words(state);
+    #[cfg(not(feature = "compact"))]
+    rounds!(w, [a, b, c, d, e, f, g, h], 0, 80, 80);
+    #[cfg(feature = "compact")]
+    rolled_rounds!(w, [a, b, c, d, e, f, g, h], 0, 80);
+    [
+        state[0].wrapping_add(a.get()),
+        state[1].wrapping_add(b.get()),
+        state[2].wrapping_add(c.get()),
+        state[3].wrapping_add(d.get()),
+        state[4].wrapping_add(e.get()),
+        state[5].wrapping_add(f.get()),
+        state[6].wrapping_add(g.get()),
+        state[7].wrapping_add(h.get()),
+    ]
+}
+
+#[inline(always)]
+pub(crate) fn compress(state: &mut [u64; 8], block: &[u64; 16]) {
+    *state = rounds(state, block);
+}
+
+#[inline(always)]
+pub(crate) const fn challenge_words(
+    r: [u64; 4],
+    key: &PreparedPublicKey,
+    digest: &PreparedDigest,
+) -> [u64; 8] {
+    let block = [
+        r[0],
+        r[1],
+        r[2],
+        r[3],
+        key.words[0],
+        key.words[1],
+        key.words[2],
+        key.words[3],
+        digest.words[0],
+        digest.words[1],
+        digest.words[2],
+        digest.words[3],
+        0x8000000000000000, // Padding bit
+        0x0000000000000000,
+        0x0000000000000000,
+        0x0000000000000300, // Message length in bits
+    ];
+    rounds(&crate::IV, &block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Rng;
+
+    #[test]
+    fn compress_matches_u64() {
+        let mut rng = Rng::new(20);
+        for _ in 0..64 {
+            let (state, block) = (rng.words(), rng.words());
+            let (mut split, mut wide) = (state, state);
+            compress(&mut split, &block);
+            crate::compress(&mut wide, &block);
+            assert_eq!(split, wide);
+        }
+    }
+
+    #[test]
+    fn challenge_words_matches_u64() {
+        let mut rng = Rng::new(21);
+        for _ in 0..16 {
+            let r = rng.words();
+            let key = PreparedPublicKey::from_words(rng.words());
+            let digest = PreparedDigest::from_words(rng.words());
+            assert_eq!(
+                challenge_words(r, &key, &digest),
+                crate::challenge_words(r, &key, &digest)
+            );
+        }
+    }
+}
@@ -0,0 +1,41 @@
+// Helpers shared by the unit tests
+
+/// Decodes a hex string of exactly `N` bytes.
+pub(crate) fn hex<const N: usize>(s: &str) -> [u8; N] {
+    assert_eq!(s.len(), N * 2);
+    let mut bytes = [0u8; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).unwrap();
+    }
+    bytes
+}
+
+/// A message of `len` bytes that is not periodic within any block.
+pub(crate) fn message(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 31 + 7) as u8).collect()
+}
+
+/// xorshift64*, a small deterministic generator so the tests need no
+/// dependencies.
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed | 1)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+
+    pub(crate) fn words<const N: usize>(&mut self) -> [u64; N] {
+        core::array::from_fn(|_| self.next_u64())
+    }
+
+    pub(crate) fn bytes<const N: usize>(&mut self) -> [u8; N] {
+        core::array::from_fn(|_| self.next_u64() as u8)
+    }
+}
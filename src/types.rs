@@ -0,0 +1,161 @@
+use crate::{hash, Error};
+
+macro_rules! bytes32 {
+    ($(#[$doc:meta])* $name:ident, $error:expr) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #[repr(transparent)]
+        pub struct $name([u8; 32]);
+
+        impl $name {
+            pub const fn new(bytes: [u8; 32]) -> Self {
+                Self(bytes)
+            }
+
+            pub const fn as_bytes(&self) -> &[u8; 32] {
+                &self.0
+            }
+
+            pub const fn to_bytes(self) -> [u8; 32] {
+                self.0
+            }
+        }
+
+        impl From<[u8; 32]> for $name {
+            fn from(bytes: [u8; 32]) -> Self {
+                Self(bytes)
+            }
+        }
+
+        impl TryFrom<&[u8]> for $name {
+            type Error = Error;
+
+            fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+                bytes.try_into().map(Self).map_err(|_| $error)
+            }
+        }
+
+        impl From<$name> for [u8; 32] {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+    };
+}
+
+bytes32!(
+    /// The R half of a signature: the encoded commitment point.
+    SignatureR,
+    Error::InvalidRLength
+);
+
+bytes32!(
+    /// An encoded Ed25519 public key, A.
+    PublicKey,
+    Error::InvalidPublicKeyLength
+);
+
+bytes32!(
+    /// The 32-byte message M that was signed.
+    MessageDigest,
+    Error::InvalidDigestLength
+);
+
+/// A 64-byte Ed25519 signature, R || S.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Signature {
+    r: SignatureR,
+    s: [u8; 32],
+}
+
+impl Signature {
+    pub fn from_bytes(bytes: &[u8; 64]) -> Self {
+        let (r, s) = bytes.split_at(32);
+        Self {
+            r: SignatureR(r.try_into().unwrap()),
+            s: s.try_into().unwrap(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&self.r.0);
+        bytes[32..].copy_from_slice(&self.s);
+        bytes
+    }
+
+    pub const fn r(&self) -> &SignatureR {
+        &self.r
+    }
+
+    /// The encoded scalar S. It is not checked to be reduced modulo L.
+    pub const fn s(&self) -> &[u8; 32] {
+        &self.s
+    }
+
+    pub const fn split(self) -> (SignatureR, [u8; 32]) {
+        (self.r, self.s)
+    }
+}
+
+impl From<[u8; 64]> for Signature {
+    fn from(bytes: [u8; 64]) -> Self {
+        Self::from_bytes(&bytes)
+    }
+}
+
+impl TryFrom<&[u8]> for Signature {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+        bytes
+            .try_into()
+            .map(Self::from_bytes)
+            .map_err(|_| Error::InvalidSignatureLength)
+    }
+}
+
+impl From<&[u8; 64]> for Signature {
+    fn from(bytes: &[u8; 64]) -> Self {
+        Self::from_bytes(bytes)
+    }
+}
+
+/// The 64-byte challenge SHA-512(R || A || M).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Challenge([u8; 64]);
+
+impl Challenge {
+    /// Typed form of [`crate::hash`].
+    #[inline(always)]
+    pub fn new(r: &SignatureR, pubkey: &PublicKey, digest: &MessageDigest) -> Self {
+        Self(hash(&r.0, &pubkey.0, &digest.0))
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; 64] {
+        &self.0
+    }
+
+    pub const fn to_bytes(self) -> [u8; 64] {
+        self.0
+    }
+}
+
+impl From<Challenge> for [u8; 64] {
+    fn from(value: Challenge) -> Self {
+        value.0
+    }
+}
+
+impl AsRef<[u8]> for Challenge {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
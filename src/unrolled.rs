@@ -0,0 +1,155 @@
+use crate::{ch, gamma0, gamma1, maj, sigma0, sigma1, PreparedDigest, PreparedPublicKey, IV, K};
+
+// Schedule words fixed by the 96-byte R || A || M
+const W12: u64 = 0x8000000000000000; // Padding bit
+const W15: u64 = 0x0000000000000300; // Message length in bits
+const G0_W12: u64 = gamma0(W12);
+const G0_W15: u64 = gamma0(W15);
+const G1_W15: u64 = gamma1(W15);
+
+// Round constants with the fixed schedule words pre-added
+const K12_W12: u64 = K[12].wrapping_add(W12);
+const K15_W15: u64 = K[15].wrapping_add(W15);
+
+// Round 0 only depends on the initial hash value and W0
+const T1_0: u64 = IV[7]
+    .wrapping_add(sigma1(IV[4]))
+    .wrapping_add(ch(IV[4], IV[5], IV[6]))
+    .wrapping_add(K[0]);
+const T2_0: u64 = sigma0(IV[0]).wrapping_add(maj(IV[0], IV[1], IV[2]));
+
+// Compresses the single padded block of a 96-byte R || A || M, with every
+// term that only depends on the padding folded into the constants above
+#[inline(always)]
+pub(crate) const fn challenge_words(
+    r: [u64; 4],
+    key: &PreparedPublicKey,
+    digest: &PreparedDigest,
+) -> [u64; 8] {
+    let mut w = [
+        r[0],
+        r[1],
+        r[2],
+        r[3],
+        key.words[0],
+        key.words[1],
+        key.words[2],
+        key.words[3],
+        digest.words[0],
+        digest.words[1],
+        digest.words[2],
+        digest.words[3],
+        0,
+        0,
+        0,
+        0,
+    ];
+
+    // Round 0
+    let t1 = T1_0.wrapping_add(w[0]);
+    let mut h = IV[6];
+    let mut g = IV[5];
+    let mut f = IV[4];
+    let mut e = IV[3].wrapping_add(t1);
+    let mut d = IV[2];
+    let mut c = IV[1];
+    let mut b = IV[0];
+    let mut a = t1.wrapping_add(T2_0);
+
+    rounds!(w, [a, b, c, d, e, f, g, h], 1, 12, 80);
+
+    // Rounds 12 to 15 only read padding words
+    round!([a, b, c, d, e, f, g, h], K12_W12);
+    round!([a, b, c, d, e, f, g, h], K[13]);
+    round!([a, b, c, d, e, f, g, h], K[14]);
+    round!([a, b, c, d, e, f, g, h], K15_W15);
+
+    // W16 to W31 still read padding words and the prepared key and digest terms
+    w[0] = w[0].wrapping_add(gamma0(w[1])).wrapping_add(w[9]);
+    round!([a, b, c, d, e, f, g, h], K[16].wrapping_add(w[0]));
+    w[1] = w[1]
+        .wrapping_add(gamma0(w[2]))
+        .wrapping_add(w[10])
+        .wrapping_add(G1_W15);
+    round!([a, b, c, d, e, f, g, h], K[17].wrapping_add(w[1]));
+    w[2] = w[2]
+        .wrapping_add(gamma0(w[3]))
+        .wrapping_add(w[11])
+        .wrapping_add(gamma1(w[0]));
+    round!([a, b, c, d, e, f, g, h], K[18].wrapping_add(w[2]));
+    w[3] = w[3]
+        .wrapping_add(key.terms[0])
+        .wrapping_add(W12)
+        .wrapping_add(gamma1(w[1]));
+    round!([a, b, c, d, e, f, g, h], K[19].wrapping_add(w[3]));
+    w[4] = key.terms[1].wrapping_add(gamma1(w[2]));
+    round!([a, b, c, d, e, f, g, h], K[20].wrapping_add(w[4]));
+    w[5] = key.terms[2].wrapping_add(gamma1(w[3]));
+    round!([a, b, c, d, e, f, g, h], K[21].wrapping_add(w[5]));
+    w[6] = key.terms[3].wrapping_add(W15).wrapping_add(gamma1(w[4]));
+    round!([a, b, c, d, e, f, g, h], K[22].wrapping_add(w[6]));
+    w[7] = w[7]
+        .wrapping_add(digest.terms[0])
+        .wrapping_add(w[0])
+        .wrapping_add(gamma1(w[5]));
+    round!([a, b, c, d, e, f, g, h], K[23].wrapping_add(w[7]));
+    w[8] = digest.terms[1]
+        .wrapping_add(w[1])
+        .wrapping_add(gamma1(w[6]));
+    round!([a, b, c, d, e, f, g, h], K[24].wrapping_add(w[8]));
+    w[9] = digest.terms[2]
+        .wrapping_add(w[2])
+        .wrapping_add(gamma1(w[7]));
+    round!([a, b, c, d, e, f, g, h], K[25].wrapping_add(w[9]));
+    w[10] = digest.terms[3]
+        .wrapping_add(w[3])
+        .wrapping_add(gamma1(w[8]));
+    round!([a, b, c, d, e, f, g, h], K[26].wrapping_add(w[10]));
+    w[11] = w[11]
+        .wrapping_add(G0_W12)
+        .wrapping_add(w[4])
+        .wrapping_add(gamma1(w[9]));
+    round!([a, b, c, d, e, f, g, h], K[27].wrapping_add(w[11]));
+    w[12] = W12.wrapping_add(w[5]).wrapping_add(gamma1(w[10]));
+    round!([a, b, c, d, e, f, g, h], K[28].wrapping_add(w[12]));
+    w[13] = w[6].wrapping_add(gamma1(w[11]));
+    round!([a, b, c, d, e, f, g, h], K[29].wrapping_add(w[13]));
+    w[14] = G0_W15.wrapping_add(w[7]).wrapping_add(gamma1(w[12]));
+    round!([a, b, c, d, e, f, g, h], K[30].wrapping_add(w[14]));
+    w[15] = W15
+        .wrapping_add(gamma0(w[0]))
+        .wrapping_add(w[8])
+        .wrapping_add(gamma1(w[13]));
+    round!([a, b, c, d, e, f, g, h], K[31].wrapping_add(w[15]));
+
+    rounds!(w, [a, b, c, d, e, f, g, h], 32, 80, 80);
+
+    // Final state values
+    [
+        IV[0].wrapping_add(a),
+        IV[1].wrapping_add(b),
+        IV[2].wrapping_add(c),
+        IV[3].wrapping_add(d),
+        IV[4].wrapping_add(e),
+        IV[5].wrapping_add(f),
+        IV[6].wrapping_add(g),
+        IV[7].wrapping_add(h),
+    ]
+}
+
+#[inline(always)]
+pub(crate) fn compress(state: &mut [u64; 8], block: &[u64; 16]) {
+    let mut w = *block;
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    rounds!(w, [a, b, c, d, e, f, g, h], 0, 80, 80);
+
+    // Final state values
+    state[0] = state[0].wrapping_add(a);
+    state[1] = state[1].wrapping_add(b);
+    state[2] = state[2].wrapping_add(c);
+    state[3] = state[3].wrapping_add(d);
+    state[4] = state[4].wrapping_add(e);
+    state[5] = state[5].wrapping_add(f);
+    state[6] = state[6].wrapping_add(g);
+    state[7] = state[7].wrapping_add(h);
+}
@@ -0,0 +1,285 @@
+use crate::{
+    hash, hash_message, EdwardsPoint, MessageDigest, PublicKey, Scalar, Signature, VerifyError,
+};
+
+/// The rules a verifier applies to the edge cases Ed25519 implementations
+/// disagree on.
+///
+/// Every policy requires S to be reduced modulo L. They differ in whether A and
+/// R may use non-canonical encodings (y >= p, or x = 0 with the sign bit set),
+/// whether small-order A and R are rejected, and whether the cofactorless
+/// `[S]B = R + [k]A` or the cofactored `[8][S]B = [8]R + [8][k]A` is checked.
+/// The two equations only disagree when A or R has a small-order component.
+///
+/// | policy                | non-canonical A | non-canonical R | small-order A, R | equation     |
+/// |-----------------------|-----------------|-----------------|------------------|--------------|
+/// | `Rfc8032Cofactorless` | rejected        | rejected        | accepted         | cofactorless |
+/// | `Rfc8032Strict`       | rejected        | rejected        | accepted         | cofactored   |
+/// | `Zip215`              | accepted        | accepted        | accepted         | cofactored   |
+/// | `DalekStrict`         | accepted        | rejected        | rejected         | cofactorless |
+///
+/// A program whose acceptance must agree with an off-chain validator has to
+/// use the same policy as that validator, as a signature that is valid under
+/// one policy can be invalid under another.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum VerifyPolicy {
+    /// RFC 8032 §5.1.7 with the cofactorless equation it allows in place of
+    /// the cofactored one: A and R must be canonical encodings, small-order
+    /// points are allowed, and `[S]B = R + [k]A` must hold exactly.
+    #[default]
+    Rfc8032Cofactorless,
+    /// RFC 8032 §5.1.7 as written: A and R must be canonical encodings and
+    /// the cofactored equation must hold. Small-order points are allowed.
+    Rfc8032Strict,
+    /// ZIP 215, as used by Zcash and ed25519-zebra: any encoding that decodes
+    /// to a point is allowed, including small-order points, and the cofactored
+    /// equation must hold. Batch and single verification always agree.
+    Zip215,
+    /// ed25519-dalek's `verify_strict`: A and R must not be of small order, R
+    /// must be canonical, and the cofactorless equation must hold. A is decoded
+    /// like ed25519-dalek does, so y >= p is reduced modulo p.
+    DalekStrict,
+}
+
+/// Verifies an Ed25519 signature over a 32-byte message, using [`crate::hash`]
+/// for the challenge, under the default [`VerifyPolicy::Rfc8032Cofactorless`].
+pub fn verify(
+    pubkey: &PublicKey,
+    digest: &MessageDigest,
+    signature: &Signature,
+) -> Result<(), VerifyError> {
+    verify_with_policy(pubkey, digest, signature, VerifyPolicy::default())
+}
+
+/// Verifies an Ed25519 signature over a 32-byte message under `policy`.
+pub fn verify_with_policy(
+    pubkey: &PublicKey,
+    digest: &MessageDigest,
+    signature: &Signature,
+    policy: VerifyPolicy,
+) -> Result<(), VerifyError> {
+    verify_with(pubkey, signature, policy, |r| {
+        Scalar::from_bytes_mod_order_wide(&hash(r, pubkey.as_bytes(), digest.as_bytes()))
+    })
+}
+
+/// Verifies an Ed25519 signature over a message of any length, using
+/// [`hash_message`] for the challenge. Otherwise the same as [`verify`].
+pub fn verify_message(
+    pubkey: &PublicKey,
+    msg: &[u8],
+    signature: &Signature,
+) -> Result<(), VerifyError> {
+    verify_message_with_policy(pubkey, msg, signature, VerifyPolicy::default())
+}
+
+/// Verifies an Ed25519 signature over a message of any length under `policy`.
+pub fn verify_message_with_policy(
+    pubkey: &PublicKey,
+    msg: &[u8],
+    signature: &Signature,
+    policy: VerifyPolicy,
+) -> Result<(), VerifyError> {
+    verify_with(pubkey, signature, policy, |r| {
+        Scalar::from_bytes_mod_order_wide(&hash_message(r, pubkey.as_bytes(), msg))
+    })
+}
+
+#[inline(always)]
+fn verify_with(
+    pubkey: &PublicKey,
+    signature: &Signature,
+    policy: VerifyPolicy,
+    challenge: impl FnOnce(&[u8; 32]) -> Scalar,
+) -> Result<(), VerifyError> {
+    let s = Scalar::from_canonical_bytes(signature.s()).ok_or(VerifyError::InvalidS)?;
+    let (a, r) = match policy {
+        VerifyPolicy::Rfc8032Cofactorless | VerifyPolicy::Rfc8032Strict => (
+            EdwardsPoint::decompress(pubkey.as_bytes()),
+            EdwardsPoint::decompress(signature.r().as_bytes()),
+        ),
+        VerifyPolicy::Zip215 => (
+            EdwardsPoint::decompress_lenient(pubkey.as_bytes()),
+            EdwardsPoint::decompress_lenient(signature.r().as_bytes()),
+        ),
+        VerifyPolicy::DalekStrict => (
+            EdwardsPoint::decompress_lenient(pubkey.as_bytes()),
+            EdwardsPoint::decompress(signature.r().as_bytes()),
+        ),
+    };
+    let a = a.ok_or(VerifyError::InvalidPublicKey)?;
+    let r = r.ok_or(VerifyError::InvalidR)?;
+    if policy == VerifyPolicy::DalekStrict {
+        if a.is_small_order() {
+            return Err(VerifyError::InvalidPublicKey);
+        }
+        if r.is_small_order() {
+            return Err(VerifyError::InvalidR);
+        }
+    }
+    let k = challenge(signature.r().as_bytes());
+
+    // [S]B - [k]A, which is R for a valid signature
+    let check = EdwardsPoint::vartime_double_scalar_mul_basepoint(&k, &-a, &s);
+    let valid = match policy {
+        VerifyPolicy::Rfc8032Cofactorless | VerifyPolicy::DalekStrict => check == r,
+        _ => (check - r).mul_by_cofactor().is_identity(),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(VerifyError::InvalidSignature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::hex;
+    use VerifyError::*;
+
+    const POLICIES: [VerifyPolicy; 4] = [
+        VerifyPolicy::Rfc8032Cofactorless,
+        VerifyPolicy::Rfc8032Strict,
+        VerifyPolicy::Zip215,
+        VerifyPolicy::DalekStrict,
+    ];
+
+    // (message, public key, signature) and the result under each of `POLICIES`
+    type Vector = (
+        &'static str,
+        &'static str,
+        &'static str,
+        [Result<(), VerifyError>; 4],
+    );
+
+    // The edge cases of "Taming the many EdDSAs" (Chalkias, Garillot and
+    // Nikolaenko, 2020)
+    #[rustfmt::skip]
+    const VECTORS: [Vector; 12] = [
+        // 0: small-order A and R, S = 0
+        (
+            "8c93255d71dcab10e8f379c26200f3c7bd5f09d9bc3068d3ef4edeb4853022b6",
+            "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac03fa",
+            "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a\
+             0000000000000000000000000000000000000000000000000000000000000000",
+            [Ok(()), Ok(()), Ok(()), Err(InvalidPublicKey)],
+        ),
+        // 1: small-order A, mixed-order R
+        (
+            "9bd9f44f4dcc75bd531b56b2cd280b0bb38fc1cd6d1230e14861d861de092e79",
+            "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac03fa",
+            "f7badec5b8abeaf699583992219b7b223f1df3fbbea919844e3f7c554a43dd43\
+             a5bb704786be79fc476f91d3f3f89b03984d8068dcf1bb7dfc6637b45450ac04",
+            [Ok(()), Ok(()), Ok(()), Err(InvalidPublicKey)],
+        ),
+        // 2: mixed-order A, small-order R
+        (
+            "aebf3f2601a0c8c5d39cc7d8911642f740b78168218da8471772b35f9d35b9ab",
+            "f7badec5b8abeaf699583992219b7b223f1df3fbbea919844e3f7c554a43dd43",
+            "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac03fa\
+             8c4bd45aecaca5b24fb97bc10ac27ac8751a7dfe1baff8b953ec9f5833ca260e",
+            [Ok(()), Ok(()), Ok(()), Err(InvalidR)],
+        ),
+        // 3: mixed-order A and R, passing both equations
+        (
+            "9bd9f44f4dcc75bd531b56b2cd280b0bb38fc1cd6d1230e14861d861de092e79",
+            "cdb267ce40c5cd45306fa5d2f29731459387dbf9eb933b7bd5aed9a765b88d4d",
+            "9046a64750444938de19f227bb80485e92b83fdb4b6506c160484c016cc1852f\
+             87909e14428a7a1d62e9f22f3d3ad7802db02eb2e688b6c52fcd6648a98bd009",
+            [Ok(()), Ok(()), Ok(()), Ok(())],
+        ),
+        // 4: mixed-order A and R, passing only the cofactored equation
+        (
+            "e47d62c63f830dc7a6851a0b1f33ae4bb2f507fb6cffec4011eaccd55b53f56c",
+            "cdb267ce40c5cd45306fa5d2f29731459387dbf9eb933b7bd5aed9a765b88d4d",
+            "160a1cb0dc9c0258cd0a7d23e94d8fa878bcb1925f2c64246b2dee1796bed512\
+             5ec6bc982a269b723e0668e540911a9a6a58921d6925e434ab10aa7940551a09",
+            [Err(InvalidSignature), Ok(()), Ok(()), Err(InvalidSignature)],
+        ),
+        // 5: mixed-order A and prime-order R, passing only the cofactored
+        // equation
+        (
+            "e47d62c63f830dc7a6851a0b1f33ae4bb2f507fb6cffec4011eaccd55b53f56c",
+            "cdb267ce40c5cd45306fa5d2f29731459387dbf9eb933b7bd5aed9a765b88d4d",
+            "21122a84e0b5fca4052f5b1235c80a537878b38f3142356b2c2384ebad4668b7\
+             e40bc836dac0f71076f9abe3a53f9c03c1ceeeddb658d0030494ace586687405",
+            [Err(InvalidSignature), Ok(()), Ok(()), Err(InvalidSignature)],
+        ),
+        // 6: S >= L
+        (
+            "85e241a07d148b41e47d62c63f830dc7a6851a0b1f33ae4bb2f507fb6cffec40",
+            "442aad9f089ad9e14647b1ef9099a1ff4798d78589e66f28eca69c11f582a623",
+            "e96f66be976d82e60150baecff9906684aebb1ef181f67a7189ac78ea23b6c0e\
+             547f7690a0e2ddcd04d87dbc3490dc19b3b3052f7ff0538cb68afb369ba3a514",
+            [Err(InvalidS), Err(InvalidS), Err(InvalidS), Err(InvalidS)],
+        ),
+        // 7: S >= L, in the high bits only
+        (
+            "85e241a07d148b41e47d62c63f830dc7a6851a0b1f33ae4bb2f507fb6cffec40",
+            "442aad9f089ad9e14647b1ef9099a1ff4798d78589e66f28eca69c11f582a623",
+            "8ce5b96c8f26d0ab6c47958c9e68b937104cd36e13c33566acd2fe8d38aa1942\
+             7e71f98a473474f2f13f06f97c20d58cc3f54b8bd0d272f42b695dd7e89a8c22",
+            [Err(InvalidS), Err(InvalidS), Err(InvalidS), Err(InvalidS)],
+        ),
+        // 8: R = (0, -1), small order but canonically encoded
+        (
+            "9bedc267423725d473888631ebf45988bad3db83851ee85c85e241a07d148b41",
+            "f7badec5b8abeaf699583992219b7b223f1df3fbbea919844e3f7c554a43dd43",
+            "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f\
+             03be9678ac102edcd92b0210bb34d7428d12ffc5df5f37e359941266a4e35f0f",
+            [Ok(()), Ok(()), Ok(()), Err(InvalidR)],
+        ),
+        // 9: R = (0, -1) with the sign bit set, a non-canonical encoding
+        (
+            "9bedc267423725d473888631ebf45988bad3db83851ee85c85e241a07d148b41",
+            "f7badec5b8abeaf699583992219b7b223f1df3fbbea919844e3f7c554a43dd43",
+            "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+             ca8c5b64cd208982aa38d4936621a4775aa233aa0505711d8fdcfdaa943d4908",
+            [Err(InvalidR), Err(InvalidR), Ok(()), Err(InvalidR)],
+        ),
+        // 10: A = (0, -1) with the sign bit set
+        (
+            "e96b7021eb39c1a163b6da4e3093dcd3f21387da4cc4572be588fafae23c155b",
+            "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "a9d55260f765261eb9b84e106f665e00b867287a761990d7135963ee0a7d59dc\
+             a5bb704786be79fc476f91d3f3f89b03984d8068dcf1bb7dfc6637b45450ac04",
+            [Err(InvalidPublicKey), Err(InvalidPublicKey), Ok(()), Err(InvalidPublicKey)],
+        ),
+        // 11: as 10, over a different message
+        (
+            "39a591f5321bbe07fd5a23dc2f39d025d74526615746727ceefd6e82ae65c06f",
+            "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "a9d55260f765261eb9b84e106f665e00b867287a761990d7135963ee0a7d59dc\
+             a5bb704786be79fc476f91d3f3f89b03984d8068dcf1bb7dfc6637b45450ac04",
+            [Err(InvalidPublicKey), Err(InvalidPublicKey), Ok(()), Err(InvalidPublicKey)],
+        ),
+    ];
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn edge_case_vectors() {
+        for (i, (msg, pubkey, signature, expected)) in VECTORS.iter().enumerate() {
+            let (msg, pubkey) = (hex::<32>(msg), PublicKey::new(hex(pubkey)));
+            let signature = Signature::from_bytes(&hex(signature));
+            for (policy, expected) in POLICIES.iter().zip(expected) {
+                let digest = MessageDigest::new(msg);
+                assert_eq!(
+                    verify_with_policy(&pubkey, &digest, &signature, *policy),
+                    *expected,
+                    "vector {i} under {policy:?}"
+                );
+                assert_eq!(
+                    verify_message_with_policy(&pubkey, &msg, &signature, *policy),
+                    *expected,
+                    "vector {i} under {policy:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn default_policy_is_rfc8032_cofactorless() {
+        assert_eq!(VerifyPolicy::default(), VerifyPolicy::Rfc8032Cofactorless);
+    }
+}